@@ -0,0 +1,299 @@
+use crate::dictionary::Entry;
+use crate::objects::prelude::*;
+use crate::source::Span;
+use crate::state::State;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::rc::{Rc, Weak};
+
+/// Records which compiled code was executed, keyed by source position.
+///
+/// Code is registered when it is compiled: the bodies of words and the quotations created with
+/// `[ ... ]`. Each registered list counts how often it was called. Clones share their records.
+#[derive(Clone, Default)]
+pub struct Coverage(Rc<RefCell<Records>>);
+
+#[derive(Default)]
+struct Records {
+    blocks: Vec<Block>,
+    /// index into `blocks` by address of the compiled list
+    index: HashMap<*const Vec<Object>, usize>,
+}
+
+struct Block {
+    /// keeps the address of the list from being reused, without keeping the list alive
+    _code: Weak<Vec<Object>>,
+    span: Span,
+    /// qualified name for word bodies, `None` for quotations
+    word: Option<String>,
+    hits: usize,
+}
+
+impl Coverage {
+    pub fn new() -> Self {
+        Coverage::default()
+    }
+
+    /// Register the body of a newly defined word.
+    pub fn add_word(&self, entry: &Entry) {
+        if let (Object::List(code), Some(span)) = (entry.word.inner(), &entry.location) {
+            self.add(code, span.clone(), Some(entry.qualified_name()));
+        }
+    }
+
+    /// Register a quotation and where it was written.
+    pub fn add_quotation(&self, quot: &Object, span: Span) {
+        if let Object::List(code) = quot {
+            self.add(code, span, None);
+        }
+    }
+
+    fn add(&self, code: &Rc<Vec<Object>>, span: Span, word: Option<String>) {
+        let records = &mut *self.0.borrow_mut();
+        let i = records.blocks.len();
+        records.blocks.push(Block {
+            _code: Rc::downgrade(code),
+            span,
+            word,
+            hits: 0,
+        });
+        records.index.insert(Rc::as_ptr(code), i);
+    }
+
+    /// Count an execution of the code, if it is registered.
+    pub fn hit(&self, code: &Object) {
+        if let Object::List(code) = code {
+            let records = &mut *self.0.borrow_mut();
+            if let Some(&i) = records.index.get(&Rc::as_ptr(code)) {
+                records.blocks[i].hits += 1;
+            }
+        }
+    }
+
+    /// The records in lcov tracefile format, one section per source file.
+    ///
+    /// Words are reported as functions and quotations as branches of the code they appear in.
+    /// A line counts as executed as often as the innermost code that contains it entirely. Code
+    /// that was not read from a file (such as `<input>`) is left out.
+    pub fn lcov(&self) -> String {
+        let records = self.0.borrow();
+
+        // merge code that was compiled more than once from the same place
+        let mut files: BTreeMap<&str, BTreeMap<(usize, usize), FileBlock>> = BTreeMap::new();
+        for block in &records.blocks {
+            let source = &block.span.source;
+            if source.name.starts_with('<') {
+                continue;
+            }
+            let merged = files
+                .entry(&source.name)
+                .or_default()
+                .entry((block.span.start, block.span.end))
+                .or_insert_with(|| FileBlock {
+                    span: &block.span,
+                    word: block.word.as_deref(),
+                    hits: 0,
+                });
+            merged.hits += block.hits;
+        }
+
+        let mut out = String::new();
+        for (name, blocks) in files {
+            let blocks: Vec<_> = blocks.into_values().collect();
+            writeln!(out, "TN:\nSF:{}", name).unwrap();
+            write_functions(&mut out, &blocks);
+            write_branches(&mut out, &blocks);
+            write_lines(&mut out, &blocks);
+            writeln!(out, "end_of_record").unwrap();
+        }
+        out
+    }
+}
+
+impl std::fmt::Debug for Coverage {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Coverage")
+    }
+}
+
+/// Registered code of one source file, sorted by position
+struct FileBlock<'a> {
+    span: &'a Span,
+    word: Option<&'a str>,
+    hits: usize,
+}
+
+impl FileBlock<'_> {
+    fn len(&self) -> usize {
+        self.span.end - self.span.start
+    }
+
+    fn contains(&self, start: usize, end: usize) -> bool {
+        self.span.start <= start && end <= self.span.end
+    }
+}
+
+/// The smallest block that contains the byte range, other than `except`
+fn innermost<'a>(
+    blocks: &'a [FileBlock<'a>],
+    start: usize,
+    end: usize,
+    except: Option<usize>,
+) -> Option<(usize, &'a FileBlock<'a>)> {
+    blocks
+        .iter()
+        .enumerate()
+        .filter(|&(i, block)| Some(i) != except && block.contains(start, end))
+        .min_by_key(|(_, block)| block.len())
+}
+
+fn write_functions(out: &mut String, blocks: &[FileBlock]) {
+    let words: Vec<_> = blocks
+        .iter()
+        .filter_map(|block| block.word.map(|name| (name, block)))
+        .collect();
+    for (name, block) in &words {
+        writeln!(out, "FN:{},{}", block.span.lines().0, name).unwrap();
+    }
+    for (name, block) in &words {
+        writeln!(out, "FNDA:{},{}", block.hits, name).unwrap();
+    }
+    let hit = words.iter().filter(|(_, block)| block.hits > 0).count();
+    writeln!(out, "FNF:{}\nFNH:{}", words.len(), hit).unwrap();
+}
+
+fn write_branches(out: &mut String, blocks: &[FileBlock]) {
+    let mut branches_in: HashMap<usize, usize> = HashMap::new();
+    let (mut found, mut hit) = (0, 0);
+    for (i, block) in blocks.iter().enumerate() {
+        if block.word.is_some() {
+            continue;
+        }
+        let outer = innermost(blocks, block.span.start, block.span.end, Some(i));
+        let outer_index = outer.map_or(blocks.len(), |(j, _)| j);
+        let branch = branches_in.entry(outer_index).or_default();
+        let taken = match outer {
+            Some((_, outer)) if outer.hits == 0 => "-".to_string(),
+            _ => block.hits.to_string(),
+        };
+        writeln!(
+            out,
+            "BRDA:{},{},{},{}",
+            block.span.lines().0,
+            outer_index,
+            branch,
+            taken
+        )
+        .unwrap();
+        *branch += 1;
+        found += 1;
+        if block.hits > 0 {
+            hit += 1;
+        }
+    }
+    writeln!(out, "BRF:{}\nBRH:{}", found, hit).unwrap();
+}
+
+fn write_lines(out: &mut String, blocks: &[FileBlock]) {
+    let source = match blocks.first() {
+        Some(block) => &block.span.source,
+        None => return,
+    };
+
+    let (mut found, mut hit) = (0, 0);
+    let mut offset = 0;
+    for (number, line) in source.text.split('\n').enumerate() {
+        let line_start = offset;
+        offset += line.len() + 1;
+
+        let content = line.trim();
+        if content.is_empty() {
+            continue;
+        }
+        let start = line_start + (line.len() - line.trim_start().len());
+        let end = start + content.len();
+        if let Some((_, block)) = innermost(blocks, start, end, None) {
+            writeln!(out, "DA:{},{}", number + 1, block.hits).unwrap();
+            found += 1;
+            if block.hits > 0 {
+                hit += 1;
+            }
+        }
+    }
+    writeln!(out, "LF:{}\nLH:{}", found, hit).unwrap();
+}
+
+/// Coverage
+impl State {
+    /// Count an execution of compiled code if coverage is recorded.
+    pub fn cover(&self, code: &Object) {
+        if let Some(coverage) = self.coverage() {
+            coverage.hit(code);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::Word;
+    use crate::source::Source;
+    use crate::stack_effects::IntoStackEffect;
+
+    #[test]
+    fn lcov_records() {
+        let text = ": both ( -- x y )\n  [ one ]\n  [ one ] ;\n";
+        let source = Rc::new(Source::new("lib.fn", text));
+        let quotation_span = |nth: usize| {
+            let start = text.match_indices("[ one ]").nth(nth).unwrap().0;
+            Span::new(source.clone(), start, start + "[ one ]".len())
+        };
+
+        let state = &mut State::new();
+        let coverage = Coverage::new();
+        state.set_coverage(Some(coverage.clone()));
+
+        state.add_native_word("one", "( -- x)", None, |state| state.push(1));
+        let one = Object::Word(state.lookup("one").unwrap().unwrap());
+        let first = Object::List(Rc::new(vec![one.clone()]));
+        let second = Object::List(Rc::new(vec![one]));
+        coverage.add_quotation(&first, quotation_span(0));
+        coverage.add_quotation(&second, quotation_span(1));
+
+        let body = Object::List(Rc::new(vec![first.clone(), second]));
+        let location = Span::new(source.clone(), 0, text.len() - 1);
+        state.add_entry(
+            Entry::new(
+                Rc::new("both".to_string()),
+                Word::Word(body),
+                "( -- x y )".into_stack_effect(),
+            )
+            .with_location(Some(location)),
+        );
+
+        state.run("both").unwrap();
+        state.pop().unwrap();
+        state.pop().unwrap().call(state).unwrap();
+        state.assert_stack(&[1]);
+
+        assert_eq!(
+            coverage.lcov(),
+            "TN:\nSF:lib.fn\nFN:1,::both\nFNDA:1,::both\nFNF:1\nFNH:1\n\
+             BRDA:2,0,0,1\nBRDA:3,0,1,0\nBRF:2\nBRH:1\n\
+             DA:1,1\nDA:2,1\nDA:3,1\nLF:3\nLH:3\nend_of_record\n"
+        );
+
+        // code typed at the prompt is not reported
+        let prompt = Rc::new(Source::new("<input>", "[ ]"));
+        let empty = Object::List(Rc::new(vec![]));
+        coverage.add_quotation(&empty, Span::new(prompt, 0, 3));
+        empty.call(state).unwrap();
+        assert_eq!(coverage.lcov().matches("SF:").count(), 1);
+
+        // registering code does not keep it alive
+        if let Object::List(ref code) = empty {
+            assert_eq!(Rc::strong_count(code), 1);
+        }
+    }
+}
@@ -3,6 +3,7 @@ use std::rc::Rc;
 
 use crate::objects::Object;
 use crate::rcstring::RcString;
+use crate::source::Span;
 use crate::vm::ByteCode;
 use crate::StackEffect;
 
@@ -26,6 +27,67 @@ pub struct Entry {
     pub word: Word,
     pub source: Option<Rc<ByteCode>>,
     pub stack_effect: StackEffect,
+    /// Path of the module the word was defined in
+    pub module: String,
+    /// Names of the local variables of words defined with `::`, indexed by storage location
+    pub locals: Option<Vec<String>>,
+    /// Where the word was defined in forthen source code
+    pub location: Option<Span>,
+    /// Documentation string
+    pub doc: Option<String>,
+}
+
+impl Entry {
+    pub fn new(name: Rc<String>, word: Word, stack_effect: StackEffect) -> Self {
+        Entry {
+            name,
+            word,
+            source: None,
+            stack_effect,
+            module: String::new(),
+            locals: None,
+            location: None,
+            doc: None,
+        }
+    }
+
+    pub fn with_locals(mut self, locals: Vec<String>) -> Self {
+        self.locals = Some(locals);
+        self
+    }
+
+    pub fn with_location(mut self, location: Option<Span>) -> Self {
+        self.location = location;
+        self
+    }
+
+    pub fn with_doc(mut self, doc: Option<String>) -> Self {
+        self.doc = doc;
+        self
+    }
+
+    /// The absolute path of the word, as accepted by `USE`.
+    pub fn qualified_name(&self) -> String {
+        format!(":{}:{}", self.module, self.name)
+    }
+
+    /// True if the word is implemented in Rust rather than compiled from forthen code.
+    pub fn is_native(&self) -> bool {
+        matches!(self.word.inner(), Object::Function(_))
+    }
+
+    /// True if the word is executed at parse time.
+    pub fn is_parsing(&self) -> bool {
+        matches!(self.word, Word::ParsingWord(_))
+    }
+
+    /// The compiled body of a compound word, or `None` for native words.
+    pub fn body(&self) -> Option<&[Object]> {
+        match self.word.inner() {
+            Object::List(list) => Some(list),
+            _ => None,
+        }
+    }
 }
 
 pub type WordId = Rc<Entry>;
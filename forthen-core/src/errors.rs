@@ -2,6 +2,10 @@ error_chain! {
     links {
     }
 
+    foreign_links {
+        Io(std::io::Error);
+    }
+
     errors {
         // parsing errors
         EndOfInput
@@ -10,6 +14,9 @@ error_chain! {
         }
         ExpectedStackEffect
         PathError
+        FileNotFound(name: String) {
+            display("File not found: {}", name)
+        }
 
         // stack effect errors
         IncompatibleStackEffects
@@ -23,6 +30,9 @@ error_chain! {
             display("Unkown Word: {}", word)
         }
         StackUnderflow
+        AccessDenied(what: String) {
+            display("Access Denied: {}", what)
+        }
 
         // type system errors
         TypeError(t: String) {
@@ -41,5 +51,18 @@ error_chain! {
         RuntimeError(msg: String) {
             display("Runtime Error: {}", msg)
         }
+
+        AssertionError(msg: String) {
+            display("Assertion Failed: {}", msg)
+        }
+
+        IoError(path: String, msg: String) {
+            display("IO Error: {}: {}", path, msg)
+        }
+
+        // not an error; the program asked to terminate with the given status
+        Exit(code: i32) {
+            display("Exit: {}", code)
+        }
     }
 }
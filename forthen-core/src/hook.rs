@@ -0,0 +1,156 @@
+use crate::dictionary::WordId;
+use crate::errors::Result;
+use crate::state::State;
+use std::any::Any;
+
+/// Observer of word calls, such as a debugger
+///
+/// Install it with `State::add_hook`. Several hooks can be installed at once. While a hook is
+/// installed, the state keeps track of the words being executed in `State::activations`.
+pub trait CallHook {
+    fn as_any(&self) -> &dyn Any;
+
+    /// Called before a word is executed. Returning an error aborts the call.
+    fn before_call(&self, state: &mut State, word: &WordId) -> Result<()>;
+
+    /// Called after a word has been executed, even if it failed. An error of the word is
+    /// reported in preference to one returned from here.
+    fn after_call(&self, _state: &mut State, _word: &WordId) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A word that is currently executing
+#[derive(Debug, Clone)]
+pub struct Activation {
+    pub word: WordId,
+    /// Index of the item in the body of the word that is executing right now
+    pub position: usize,
+}
+
+impl std::fmt::Debug for dyn CallHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "CallHook")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ErrorKind;
+    use crate::objects::Object;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Default)]
+    struct Recorder(RefCell<Vec<String>>);
+
+    impl CallHook for Recorder {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn before_call(&self, state: &mut State, word: &WordId) -> Result<()> {
+            let caller = match state.activations().last() {
+                Some(act) => format!("{}@{}", act.word.name, act.position),
+                None => "top".to_string(),
+            };
+            self.0
+                .borrow_mut()
+                .push(format!("{} {}", caller, word.name));
+            Ok(())
+        }
+
+        fn after_call(&self, state: &mut State, word: &WordId) -> Result<()> {
+            let depth = state.activations().len();
+            self.0
+                .borrow_mut()
+                .push(format!("{} done {}", word.name, depth));
+            Ok(())
+        }
+    }
+
+    struct FailAfter;
+
+    impl CallHook for FailAfter {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn before_call(&self, _state: &mut State, _word: &WordId) -> Result<()> {
+            Ok(())
+        }
+
+        fn after_call(&self, _state: &mut State, _word: &WordId) -> Result<()> {
+            Err(ErrorKind::RuntimeError("hook".to_string()).into())
+        }
+    }
+
+    #[test]
+    fn word_errors_come_first() {
+        let state = &mut State::new();
+        state.add_native_word("fail", "( -- )", None, |_| {
+            Err(ErrorKind::RuntimeError("word".to_string()).into())
+        });
+        state.add_native_word("pass", "( -- )", None, |_| Ok(()));
+        state.add_hook(Rc::new(FailAfter));
+
+        let err = state.run("fail").unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::RuntimeError(msg) if msg == "word"));
+        let err = state.run("pass").unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::RuntimeError(msg) if msg == "hook"));
+    }
+
+    #[test]
+    fn call_hook() {
+        let state = &mut State::new();
+        state.add_native_word("one", "( -- x)", None, |state| state.push(1));
+        let one = Object::Word(state.lookup("one").unwrap().unwrap());
+        let body = vec![one.clone(), Object::I32(2), one];
+        state.add_compound_word("two", "( -- x y z)", Object::List(Rc::new(body)));
+
+        let recorder = Rc::new(Recorder::default());
+        let hook: Rc<dyn CallHook> = recorder.clone();
+        state.add_hook(hook.clone());
+        state.run("two").unwrap();
+        assert!(state.remove_hook(&hook));
+        state.run("one").unwrap();
+
+        state.assert_stack(&[1, 2, 1, 1]);
+        assert_eq!(
+            *recorder.0.borrow(),
+            vec![
+                "top two",
+                "two@0 one",
+                "one done 1",
+                "two@2 one",
+                "one done 1",
+                "two done 0"
+            ]
+        );
+    }
+
+    #[test]
+    fn several_hooks() {
+        let state = &mut State::new();
+        state.add_native_word("one", "( -- x)", None, |state| state.push(1));
+
+        let first = Rc::new(Recorder::default());
+        let second: Rc<dyn CallHook> = Rc::new(Recorder::default());
+        state.add_hook(first.clone());
+        state.add_hook(second.clone());
+        state.run("one").unwrap();
+        assert!(state.remove_hook(&second));
+        assert!(!state.remove_hook(&second));
+        state.run("one").unwrap();
+
+        assert_eq!(
+            *first.0.borrow(),
+            vec!["top one", "one done 0", "top one", "one done 0"]
+        );
+        let second = second.as_any().downcast_ref::<Recorder>().unwrap();
+        assert_eq!(*second.0.borrow(), vec!["top one", "one done 0"]);
+        assert!(state.find_hook::<Recorder>().is_some());
+        assert!(state.find_hook::<FailAfter>().is_none());
+    }
+}
@@ -0,0 +1,84 @@
+use std::cell::RefCell;
+use std::io::{self, BufRead, BufReader, Read};
+use std::rc::Rc;
+
+/// Source of data read by the input words (not the program text itself).
+///
+/// Clones share the same underlying reader, so a `State` and its substates consume the same input.
+#[derive(Clone)]
+pub struct Input(Rc<RefCell<Box<dyn BufRead>>>);
+
+impl Input {
+    pub fn new(reader: impl BufRead + 'static) -> Self {
+        Input(Rc::new(RefCell::new(Box::new(reader))))
+    }
+
+    pub fn stdin() -> Self {
+        Input::new(BufReader::new(io::stdin()))
+    }
+
+    /// Input that yields the given text and then reaches end of file
+    pub fn from_string(text: impl Into<String>) -> Self {
+        Input::new(io::Cursor::new(text.into().into_bytes()))
+    }
+
+    /// Read the next line without its line terminator, or `None` at end of file.
+    pub fn read_line(&self) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        if self.0.borrow_mut().read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        if line.ends_with('\n') {
+            line.pop();
+            if line.ends_with('\r') {
+                line.pop();
+            }
+        }
+        Ok(Some(line))
+    }
+
+    /// Read everything up to end of file.
+    pub fn read_all(&self) -> io::Result<String> {
+        let mut text = String::new();
+        self.0.borrow_mut().read_to_string(&mut text)?;
+        Ok(text)
+    }
+
+    /// Read a single (UTF-8 encoded) character, or `None` at end of file.
+    pub fn read_char(&self) -> io::Result<Option<char>> {
+        let mut reader = self.0.borrow_mut();
+
+        let mut bytes = [0; 4];
+        if reader.read(&mut bytes[..1])? == 0 {
+            return Ok(None);
+        }
+
+        let len = match bytes[0] {
+            0x00..=0x7f => 1,
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            _ => 4,
+        };
+        reader.read_exact(&mut bytes[1..len])?;
+
+        std::str::from_utf8(&bytes[..len])
+            .map(|s| s.chars().next())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn is_eof(&self) -> io::Result<bool> {
+        Ok(self.0.borrow_mut().fill_buf()?.is_empty())
+    }
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Input::from_string("")
+    }
+}
+
+impl std::fmt::Debug for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Input")
+    }
+}
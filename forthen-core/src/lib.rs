@@ -1,30 +1,50 @@
 #[macro_use]
 extern crate error_chain;
 
+mod coverage;
 mod dictionary;
 pub mod errors;
+mod hook;
+mod input;
 mod module;
 pub mod object_factory;
 pub mod objects;
-mod parsing;
+mod output;
+pub mod parsing;
+mod profile;
 mod rcstring;
 mod refhash;
+mod sandbox;
 mod scope;
+mod source;
 mod stack_effects;
 mod state;
-mod testing;
+pub mod testing;
+mod trace;
 mod vm;
 
+pub use coverage::Coverage;
+pub use dictionary::{Entry, Word, WordId};
 pub use errors::{Error, Result};
+pub use hook::{Activation, CallHook};
+pub use input::Input;
+pub use module::ModuleRef;
 pub use objects::prelude::*;
+pub use output::{Output, StringBuffer};
+pub use profile::{Profiler, WordStats};
+pub use sandbox::Sandbox;
 pub use scope::CompilerScope;
+pub use source::{Source, Span};
 pub use stack_effects::{IntoStackEffect, StackEffect};
 pub use state::{Mode, State};
+pub use trace::Tracer;
 pub use vm::{ByteCode, Opcode};
 
 #[cfg(test)]
 mod tests {
+    use crate::objects::prelude::*;
     use crate::state::State;
+    use std::rc::Rc;
 
     #[test]
     fn literals() {
@@ -37,4 +57,19 @@ mod tests {
         assert_eq!(state.pop_i32().unwrap(), 0);
         assert_eq!(state.pop_i32().unwrap(), -10);
     }
+
+    #[test]
+    fn copy_on_write() {
+        let mut list = Object::List(Rc::new(vec![Object::I32(1)]));
+        let copy = list.clone();
+        list.as_vec_mut().unwrap().push(Object::I32(2));
+        assert_eq!(list.as_slice().unwrap(), &[1, 2]);
+        assert_eq!(copy.as_slice().unwrap(), &[1]);
+
+        let mut table = Object::new_table();
+        let copy = table.clone();
+        table.set_attr(Rc::new("x".to_string()), Object::I32(3));
+        assert!(table.get_attr("x").is_some());
+        assert!(copy.get_attr("x").is_none());
+    }
 }
@@ -14,7 +14,13 @@ impl ModuleRef {
 
     /// Create a new module and add it to submodules under the given name.
     pub fn new_submodule(&self, name: String) -> ModuleRef {
+        let path = match self.path().as_str() {
+            "" => name.clone(),
+            parent => format!("{}:{}", parent, name),
+        };
+
         let newmod = Module {
+            path,
             parent: Rc::downgrade(&self.0),
             submodules: HashMap::new(),
             dict: Dictionary::new(),
@@ -27,6 +33,19 @@ impl ModuleRef {
         rcmod
     }
 
+    /// Create a module that sees the words and submodules of this module, but is not added to
+    /// its submodules. Words and modules defined in the new module stay private to it.
+    pub fn new_private_child(&self, name: String) -> ModuleRef {
+        let newmod = Module {
+            path: name,
+            parent: Rc::downgrade(&self.0),
+            submodules: self.0.borrow().submodules.clone(),
+            dict: Dictionary::new(),
+        };
+
+        ModuleRef(Rc::new(RefCell::new(newmod)))
+    }
+
     pub fn access_path(&self, path: &str) -> Option<ModuleRef> {
         let mut split = path.splitn(2, ':');
 
@@ -41,6 +60,15 @@ impl ModuleRef {
         }
     }
 
+    /// Path of this module relative to the root module
+    pub fn path(&self) -> String {
+        self.0.borrow().path.clone()
+    }
+
+    pub fn submodule_names(&self) -> Vec<String> {
+        self.0.borrow().submodules.keys().cloned().collect()
+    }
+
     pub fn parent(&self) -> Option<ModuleRef> {
         self.0.borrow().parent.upgrade().map(ModuleRef)
     }
@@ -72,6 +100,7 @@ impl ModuleRef {
 
 #[derive(Debug, Default)]
 pub struct Module {
+    path: String,
     parent: Weak<RefCell<Module>>,
     submodules: HashMap<String, ModuleRef>,
     dict: Dictionary,
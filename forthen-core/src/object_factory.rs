@@ -2,6 +2,7 @@ use std::collections::HashSet;
 use std::rc::Rc;
 
 use crate::objects::Object;
+use crate::parsing::unescape;
 use crate::rcstring::RcString;
 
 /// will be responsible for things like string and small integer reuse
@@ -19,10 +20,7 @@ impl ObjectFactory {
 
     pub fn parse(&mut self, s: &str) -> Option<Object> {
         if s.starts_with('"') && s.ends_with('"') {
-            Some(
-                self.get_string(s[1..s.len() - 1].replace(r"\n", "\n"))
-                    .into(),
-            )
+            Some(self.get_string(unescape(&s[1..s.len() - 1])).into())
         } else {
             s.parse::<i32>().ok().map(Object::from)
         }
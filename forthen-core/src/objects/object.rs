@@ -267,12 +267,18 @@ impl ObjectInterface for Object {
 
     fn call(&self, state: &mut State) -> Result<()> {
         match self {
-            Object::Word(id) => id.word.inner().call(state),
+            Object::Word(id) if state.hooks().is_empty() => id.word.inner().call(state),
+            Object::Word(id) => state.call_hooked(id),
             Object::Function(f) => f.call(state),
             Object::Table(dynobj) => dynobj.call(state),
             Object::List(list) => {
-                for item in &**list {
+                state.cover(self);
+                for (i, item) in list.iter().enumerate() {
                     if item.is_callable() {
+                        if let Object::Word(entry) = item {
+                            state.check_word(entry)?;
+                        }
+                        state.set_position(list, i);
                         item.call(state)?;
                     } else {
                         state.push(item.clone())?;
@@ -295,7 +301,7 @@ impl ObjectInterface for Object {
 
     fn as_vec_mut(&mut self) -> Result<&mut Vec<Object>> {
         match self {
-            Object::List(vec) => Rc::get_mut(vec).ok_or_else(|| ErrorKind::OwnershipError.into()),
+            Object::List(vec) => Ok(Rc::make_mut(vec)),
             Object::Table(dynobj) => dynobj.as_vec_mut(),
             _ => Err(ErrorKind::TypeError(format!("{:?} is not a list", self)).into()),
         }
@@ -312,9 +318,7 @@ impl ObjectInterface for Object {
     fn set_meta(&mut self, meta: Option<Table>) -> Result<()> {
         match self {
             Object::Table(table) => {
-                let t: Result<_> =
-                    Rc::get_mut(table).ok_or_else(|| ErrorKind::OwnershipError.into());
-                t?.set_metatable(meta);
+                Rc::make_mut(table).set_metatable(meta);
                 Ok(())
             }
             _ => Err(ErrorKind::TypeError(format!(
@@ -95,11 +95,7 @@ impl ObjectInterface for Table {
     }
 
     fn set_attr(&mut self, attr: Rc<String>, value: Object) {
-        Rc::get_mut(self)
-            .ok_or(ErrorKind::OwnershipError)
-            .unwrap()
-            .attributes
-            .insert(attr.into(), value);
+        Rc::make_mut(self).attributes.insert(attr.into(), value);
     }
 
     fn get_attr(&self, attr: &str) -> Option<Object> {
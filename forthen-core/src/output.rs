@@ -0,0 +1,71 @@
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+/// Destination of everything the interpreter prints.
+///
+/// Clones share the same underlying writer, so a `State` and its substates print to the same place.
+#[derive(Clone)]
+pub struct Output(Rc<RefCell<Box<dyn Write>>>);
+
+impl Output {
+    pub fn new(writer: impl Write + 'static) -> Self {
+        Output(Rc::new(RefCell::new(Box::new(writer))))
+    }
+
+    pub fn stdout() -> Self {
+        Output::new(io::stdout())
+    }
+
+    /// Output that collects everything written into a `StringBuffer`
+    pub fn capture() -> (Self, StringBuffer) {
+        let buffer = StringBuffer::default();
+        (Output::new(buffer.clone()), buffer)
+    }
+
+    pub fn write_fmt(&self, args: std::fmt::Arguments) -> io::Result<()> {
+        self.0.borrow_mut().write_fmt(args)
+    }
+
+    pub fn write_str(&self, text: &str) -> io::Result<()> {
+        self.0.borrow_mut().write_all(text.as_bytes())
+    }
+
+    pub fn flush(&self) -> io::Result<()> {
+        self.0.borrow_mut().flush()
+    }
+}
+
+impl Default for Output {
+    fn default() -> Self {
+        Output::stdout()
+    }
+}
+
+impl std::fmt::Debug for Output {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Output")
+    }
+}
+
+/// Shared in-memory writer
+#[derive(Debug, Clone, Default)]
+pub struct StringBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl StringBuffer {
+    /// Everything written so far; invalid UTF-8 is replaced.
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.borrow()).into_owned()
+    }
+}
+
+impl Write for StringBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
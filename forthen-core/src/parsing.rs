@@ -1,6 +1,17 @@
 pub fn tokenize(input: &str) -> impl Iterator<Item = &str> {
+    tokenize_with_offsets(input).map(|(_, token)| token)
+}
+
+/// Split input into tokens, together with each token's byte offset into the input.
+///
+/// A `#!` line at the very beginning is skipped, so scripts can be made executable.
+pub fn tokenize_with_offsets(input: &str) -> impl Iterator<Item = (usize, &str)> {
     let mut it = input.char_indices().peekable();
 
+    if input.starts_with("#!") {
+        skip_while(&mut it, |ch| ch != '\n');
+    }
+
     std::iter::repeat(())
         .map(move |_| {
             skip_while(&mut it, char::is_whitespace);
@@ -9,27 +20,34 @@ pub fn tokenize(input: &str) -> impl Iterator<Item = &str> {
                 None => None,
                 Some((_, '"')) => {
                     let (a, _) = it.next().unwrap();
-                    skip_while(&mut it, |ch| ch != '"');
-                    it.next();
+                    while let Some((_, ch)) = it.next() {
+                        match ch {
+                            '"' => break,
+                            '\\' => {
+                                it.next();
+                            }
+                            _ => {}
+                        }
+                    }
                     match it.peek() {
-                        Some((b, _)) => Some(&input[a..*b]),
-                        None => Some(&input[a..]),
+                        Some((b, _)) => Some((a, &input[a..*b])),
+                        None => Some((a, &input[a..])),
                     }
                 }
-                Some((_, '(')) => {
+                Some(&(i, '(')) => {
                     it.next();
-                    Some("(")
+                    Some((i, "("))
                 }
-                Some((_, ')')) => {
+                Some(&(i, ')')) => {
                     it.next();
-                    Some(")")
+                    Some((i, ")"))
                 }
                 Some((i, _)) => {
                     let a = *i;
                     skip_while(&mut it, |ch| ch != '(' && ch != ')' && !ch.is_whitespace());
                     match it.peek() {
-                        Some((b, _)) => Some(&input[a..*b]),
-                        None => Some(&input[a..]),
+                        Some((b, _)) => Some((a, &input[a..*b])),
+                        None => Some((a, &input[a..])),
                     }
                 }
             }
@@ -38,6 +56,64 @@ pub fn tokenize(input: &str) -> impl Iterator<Item = &str> {
         .map(Option::unwrap)
 }
 
+/// True for a string literal token that lacks its closing quote, such as `"abc` or `"abc\\"`.
+pub fn is_unterminated_string(token: &str) -> bool {
+    let mut chars = match token.strip_prefix('"') {
+        Some(body) => body.chars(),
+        None => return false,
+    };
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => return false,
+            '\\' => {
+                chars.next();
+            }
+            _ => {}
+        }
+    }
+    true
+}
+
+/// Replace the escape sequences `\\n`, `\\t`, `\\"` and `\\\\` in the body of a string literal. Other
+/// backslashes are kept as they are.
+pub fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('"') => out.push('"'),
+            Some('\\') => out.push('\\'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// The body of a string literal that `unescape` turns back into `s`.
+pub fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '\n' => out.push_str(r"\n"),
+            '\t' => out.push_str(r"\t"),
+            '"' => out.push_str(r#"\""#),
+            '\\' => out.push_str(r"\\"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
 fn skip_while(
     it: &mut std::iter::Peekable<std::str::CharIndices>,
     predicate: impl Fn(char) -> bool,
@@ -46,3 +122,29 @@ fn skip_while(
         it.next();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shebang() {
+        let tokens: Vec<_> = tokenize_with_offsets("#!/usr/bin/env forthen\n1 #!").collect();
+        assert_eq!(tokens, vec![(23, "1"), (25, "#!")]);
+        assert_eq!(tokenize("#!").count(), 0);
+    }
+
+    #[test]
+    fn string_escapes() {
+        let tokens: Vec<_> = tokenize(r#""a \" b" "c\\" d"#).collect();
+        assert_eq!(tokens, vec![r#""a \" b""#, r#""c\\""#, "d"]);
+
+        let text = "tab\tquote\" backslash\\ newline\n";
+        assert_eq!(unescape(&escape(text)), text);
+        assert_eq!(escape(text), r#"tab\tquote\" backslash\\ newline\n"#);
+        assert!(is_unterminated_string(r#""a \""#));
+        assert!(!is_unterminated_string(r#""a \\""#));
+        assert!(!is_unterminated_string("a\""));
+        assert_eq!(unescape(r"C:\dir\"), r"C:\dir\");
+    }
+}
@@ -0,0 +1,207 @@
+use crate::dictionary::WordId;
+use crate::errors::*;
+use crate::hook::CallHook;
+use crate::state::State;
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Call hook that counts calls and measures the time spent in each word.
+///
+/// Clones share their measurements.
+#[derive(Clone, Default)]
+pub struct Profiler(Rc<RefCell<Measurements>>);
+
+#[derive(Default)]
+struct Measurements {
+    /// words currently executing, innermost last
+    stack: Vec<Frame>,
+    stats: HashMap<String, WordStats>,
+    /// exclusive time per call stack, with frames separated by `;`
+    folded: HashMap<String, Duration>,
+}
+
+struct Frame {
+    name: String,
+    start: Instant,
+    /// time spent in words called from this one
+    children: Duration,
+}
+
+/// Measurements of a single word
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct WordStats {
+    pub calls: usize,
+    /// time from entering to leaving the word; recursive calls are counted only once
+    pub inclusive: Duration,
+    /// time spent in the word itself, not in the words it called
+    pub exclusive: Duration,
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Profiler::default()
+    }
+
+    /// Call `f` with the profiler installed as call hook of the state.
+    pub fn run(&self, state: &mut State, f: impl FnOnce(&mut State) -> Result<()>) -> Result<()> {
+        let hook: Rc<dyn CallHook> = Rc::new(self.clone());
+        state.add_hook(hook.clone());
+        let result = f(state);
+        state.remove_hook(&hook);
+        result
+    }
+
+    /// Measurements per word, by qualified name, sorted by decreasing exclusive time
+    pub fn stats(&self) -> Vec<(String, WordStats)> {
+        let mut stats: Vec<_> = self
+            .0
+            .borrow()
+            .stats
+            .iter()
+            .map(|(name, stats)| (name.clone(), stats.clone()))
+            .collect();
+        stats.sort_by(|(a_name, a), (b_name, b)| {
+            b.exclusive.cmp(&a.exclusive).then(a_name.cmp(b_name))
+        });
+        stats
+    }
+
+    /// Table of calls and times per word, most expensive first
+    pub fn report(&self) -> String {
+        let mut report = format!(
+            "{:>10} {:>12} {:>12}  {}\n",
+            "calls", "total ms", "self ms", "word"
+        );
+        for (name, stats) in self.stats() {
+            report += &format!(
+                "{:>10} {:>12.3} {:>12.3}  {}\n",
+                stats.calls,
+                stats.inclusive.as_secs_f64() * 1e3,
+                stats.exclusive.as_secs_f64() * 1e3,
+                name
+            );
+        }
+        report
+    }
+
+    /// Exclusive time in microseconds per call stack, in the folded format read by flame graph
+    /// tools like `inferno` or `flamegraph.pl`.
+    pub fn folded(&self) -> String {
+        let measurements = self.0.borrow();
+        let mut lines: Vec<_> = measurements
+            .folded
+            .iter()
+            .map(|(stack, time)| format!("{} {}\n", stack, time.as_micros()))
+            .collect();
+        lines.sort();
+        lines.concat()
+    }
+}
+
+impl CallHook for Profiler {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn before_call(&self, _state: &mut State, word: &WordId) -> Result<()> {
+        self.0.borrow_mut().stack.push(Frame {
+            name: word.qualified_name(),
+            start: Instant::now(),
+            children: Duration::default(),
+        });
+        Ok(())
+    }
+
+    fn after_call(&self, _state: &mut State, _word: &WordId) -> Result<()> {
+        let measurements = &mut *self.0.borrow_mut();
+        let frame = match measurements.stack.pop() {
+            Some(frame) => frame,
+            // the profiler was installed while the word was running
+            None => return Ok(()),
+        };
+
+        let elapsed = frame.start.elapsed();
+        let exclusive = elapsed.saturating_sub(frame.children);
+        if let Some(parent) = measurements.stack.last_mut() {
+            parent.children += elapsed;
+        }
+
+        let recursive = measurements
+            .stack
+            .iter()
+            .any(|outer| outer.name == frame.name);
+
+        let stats = measurements.stats.entry(frame.name.clone()).or_default();
+        stats.calls += 1;
+        stats.exclusive += exclusive;
+        if !recursive {
+            stats.inclusive += elapsed;
+        }
+
+        let mut path: Vec<&str> = measurements
+            .stack
+            .iter()
+            .map(|outer| outer.name.as_str())
+            .collect();
+        path.push(&frame.name);
+        *measurements.folded.entry(path.join(";")).or_default() += exclusive;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::Object;
+
+    #[test]
+    fn profile_calls() {
+        let state = &mut State::new();
+        state.add_native_word("one", "( -- x)", None, |state| state.push(1));
+        let one = Object::Word(state.lookup("one").unwrap().unwrap());
+        let body = vec![one.clone(), one];
+        state.add_compound_word("two", "( -- x y)", Object::List(Rc::new(body)));
+
+        let profiler = Profiler::new();
+        profiler
+            .run(state, |state| state.run("two one two"))
+            .unwrap();
+        assert!(state.hooks().is_empty());
+        state.assert_stack(&[1, 1, 1, 1, 1]);
+
+        let stats: HashMap<_, _> = profiler.stats().into_iter().collect();
+        assert_eq!(stats["::one"].calls, 5);
+        assert_eq!(stats["::two"].calls, 2);
+        assert!(stats["::two"].inclusive >= stats["::two"].exclusive);
+
+        let folded: Vec<_> = profiler
+            .folded()
+            .lines()
+            .map(|line| line.split(' ').next().unwrap().to_string())
+            .collect();
+        assert_eq!(folded, vec!["::one", "::two", "::two;::one"]);
+
+        assert!(profiler.report().lines().count() == 3);
+    }
+
+    #[test]
+    fn profile_while_tracing() {
+        let state = &mut State::new();
+        state.add_native_word("one", "( -- x)", None, |state| state.push(1));
+
+        let (output, buffer) = crate::Output::capture();
+        let tracer = state.tracer();
+        tracer.set_output(output);
+        tracer.trace_all();
+
+        let profiler = Profiler::new();
+        profiler.run(state, |state| state.run("one")).unwrap();
+        assert_eq!(profiler.stats()[0].1.calls, 1);
+        assert_eq!(buffer.contents(), "> one  []\n< one  [1]\n");
+        assert_eq!(state.hooks().len(), 1);
+    }
+}
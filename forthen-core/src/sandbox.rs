@@ -0,0 +1,97 @@
+use crate::dictionary::Entry;
+use crate::module::ModuleRef;
+use std::collections::HashSet;
+
+/// Path of the module a sandboxed `State` defines its words in
+pub(crate) const SANDBOX_MODULE: &str = "<sandbox>";
+
+/// Allow-list of the modules and words a sandboxed `State` may access.
+///
+/// Module paths are written without leading colon ("" is the root module, "std:stack" a submodule)
+/// and match exactly; allowing a module does not allow its submodules.
+/// Modules created by the sandboxed code itself are always accessible.
+#[derive(Debug, Clone, Default)]
+pub struct Sandbox {
+    modules: HashSet<String>,
+    words: HashSet<(String, String)>,
+    include: bool,
+    /// module the sandboxed code defines words in; submodules only refer to it weakly
+    home: Option<ModuleRef>,
+}
+
+impl Sandbox {
+    /// A sandbox that allows nothing
+    pub fn new() -> Self {
+        Sandbox::default()
+    }
+
+    /// Pure computation: the root module and the standard library without `std:input`, `std:sys`
+    /// and `std:timeit`, and no file access.
+    pub fn pure() -> Self {
+        [
+            "",
+            "std",
+            "std:branch",
+            "std:introspect",
+            "std:list",
+            "std:loop",
+            "std:ops",
+            "std:scope",
+            "std:stack",
+            "std:table",
+        ]
+        .iter()
+        .fold(Sandbox::new(), |sandbox, path| sandbox.allow_module(path))
+    }
+
+    /// Allow all words defined in the module
+    pub fn allow_module(mut self, path: &str) -> Self {
+        self.modules.insert(normalize(path).to_string());
+        self
+    }
+
+    /// Allow a single word defined in the module
+    pub fn allow_word(mut self, path: &str, name: &str) -> Self {
+        self.words
+            .insert((normalize(path).to_string(), name.to_string()));
+        self
+    }
+
+    /// Allow loading source files with `INCLUDE`
+    pub fn allow_include(mut self) -> Self {
+        self.include = true;
+        self
+    }
+
+    /// Test if the module, or at least one of its words, is allowed.
+    pub fn allows_module(&self, path: &str) -> bool {
+        let path = normalize(path);
+        self.modules.contains(path) || self.words.iter().any(|(module, _)| module == path)
+    }
+
+    pub fn allows_word(&self, entry: &Entry) -> bool {
+        self.modules.contains(&entry.module)
+            || self
+                .words
+                .contains(&(entry.module.clone(), entry.name.to_string()))
+    }
+
+    pub fn allows_include(&self) -> bool {
+        self.include
+    }
+
+    /// Allow a module that was created inside the sandbox.
+    pub(crate) fn add_own_module(&mut self, path: String) {
+        self.modules.insert(path);
+    }
+
+    /// Set the module that the sandboxed code defines words in.
+    pub(crate) fn set_home(&mut self, module: ModuleRef) {
+        self.add_own_module(module.path());
+        self.home = Some(module);
+    }
+}
+
+fn normalize(path: &str) -> &str {
+    path.trim_start_matches(':').trim_end_matches(':')
+}
@@ -17,6 +17,15 @@ impl CompilerScope {
         *self.variables.entry(var.to_string()).or_insert(n)
     }
 
+    /// Variable names ordered by storage location
+    pub fn names(&self) -> Vec<String> {
+        let mut names = vec![String::new(); self.variables.len()];
+        for (name, &i) in &self.variables {
+            names[i] = name.clone();
+        }
+        names
+    }
+
     pub fn is_empty(&self) -> bool {
         self.variables.is_empty()
     }
@@ -0,0 +1,70 @@
+use std::rc::Rc;
+
+/// Program text together with the name of where it came from (usually a file name)
+#[derive(Debug)]
+pub struct Source {
+    pub name: String,
+    pub text: String,
+}
+
+impl Source {
+    pub fn new(name: impl Into<String>, text: impl Into<String>) -> Self {
+        Source {
+            name: name.into(),
+            text: text.into(),
+        }
+    }
+
+    /// 1-based line number of a byte offset into the text
+    pub fn line_of(&self, offset: usize) -> usize {
+        1 + self.text[..offset].matches('\n').count()
+    }
+}
+
+/// A range of bytes in a `Source`
+#[derive(Debug, Clone)]
+pub struct Span {
+    pub source: Rc<Source>,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(source: Rc<Source>, start: usize, end: usize) -> Self {
+        Span { source, start, end }
+    }
+
+    /// Span from the beginning of this span to the end of another span in the same source.
+    pub fn to(&self, other: &Span) -> Span {
+        Span::new(self.source.clone(), self.start, other.end)
+    }
+
+    /// The verbatim source text covered by the span
+    pub fn text(&self) -> &str {
+        &self.source.text[self.start..self.end]
+    }
+
+    /// First and last line (1-based, inclusive) covered by the span
+    pub fn lines(&self) -> (usize, usize) {
+        (
+            self.source.line_of(self.start),
+            self.source.line_of(self.end),
+        )
+    }
+}
+
+impl std::fmt::Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.lines() {
+            (first, last) if first == last => write!(f, "{}:{}", self.source.name, first),
+            (first, last) => write!(f, "{}:{}-{}", self.source.name, first, last),
+        }
+    }
+}
+
+/// A token read from a `Source`
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub text: String,
+    pub span: Span,
+}
@@ -55,6 +55,30 @@ impl StackEffect {
         )
     }
 
+    /// Names of the items consumed, bottom first, and the number of items produced, if the effect
+    /// only touches a fixed number of items on top of the stack. `None` for effects like
+    /// `(..a f(..a -- ..b) -- ..b)`.
+    pub fn fixed_arity(&self) -> Option<(Vec<String>, usize)> {
+        match (self.inputs.first(), self.outputs.first()) {
+            (Some(a), Some(b)) if a.is_same(b) && a.borrow().is_ellipsis() => {}
+            _ => return None,
+        }
+        let inputs = &self.inputs[1..];
+        let outputs = &self.outputs[1..];
+        if inputs
+            .iter()
+            .chain(outputs)
+            .any(|x| x.borrow().is_ellipsis() || x.borrow().is_sequence())
+        {
+            return None;
+        }
+        let names = inputs
+            .iter()
+            .map(|x| x.borrow().name().unwrap_or_default().to_string())
+            .collect();
+        Some((names, outputs.len()))
+    }
+
     pub fn parse(input: &str) -> Result<Self> {
         let scrpad = &mut Scratchpad::default();
         parse_effect(scrpad, &mut tokenize(input).peekable()).map_err(|e| e)
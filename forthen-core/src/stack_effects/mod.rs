@@ -113,4 +113,17 @@ mod tests {
             StackEffect::parse("(g( g -- y( a -- a b ) ) -- y )")
         );
     }
+
+    #[test]
+    fn fixed_arity() {
+        let arity = |effect: &str| StackEffect::parse(effect).unwrap().fixed_arity();
+        let names = |names: &[&str]| names.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(arity("( -- )"), Some((vec![], 0)));
+        assert_eq!(arity("(a b -- a b a b)"), Some((names(&["a", "b"]), 4)));
+        assert_eq!(arity("(x f( x -- y ) -- y)"), Some((names(&["x", "f"]), 1)));
+        assert_eq!(arity("(..a x -- ..a)"), Some((names(&["x"]), 0)));
+        assert_eq!(arity("(..a f(..a -- ..b) -- ..b)"), None);
+        assert_eq!(arity("(..a x -- ..b)"), None);
+    }
 }
@@ -1,14 +1,21 @@
 use std::borrow::Borrow;
 use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
+use crate::coverage::Coverage;
 use crate::dictionary::{Entry, Word, WordId};
 use crate::errors::*;
+use crate::hook::{Activation, CallHook};
+use crate::input::Input;
 use crate::module::ModuleRef;
 use crate::object_factory::{ObjectFactory, StringManager};
 use crate::objects::{callable::Callable, prelude::*};
-use crate::parsing::tokenize;
+use crate::output::Output;
+use crate::parsing::tokenize_with_offsets;
+use crate::sandbox::{Sandbox, SANDBOX_MODULE};
 use crate::scope::CompilerScope;
+use crate::source::{Source, Span, Token};
 use crate::stack_effects::{IntoStackEffect, StackEffect};
 
 #[derive(Debug, Copy, Clone)]
@@ -25,7 +32,13 @@ impl Default for Mode {
 
 #[derive(Debug, Default)]
 pub struct State {
-    input_tokens: VecDeque<String>,
+    /// One token queue per (possibly nested) call of `run_source`
+    input_sources: Vec<VecDeque<Token>>,
+    last_span: Option<Span>,
+    /// Doc strings set with `DOC`, one per (possibly nested) call of `compile`
+    docs: Vec<Option<String>>,
+    /// Doc string of the code compiled last
+    finished_doc: Option<String>,
     pub stack: Vec<Object>,
     pub frames: Vec<Object>,
     pub factory: ObjectFactory,
@@ -33,6 +46,14 @@ pub struct State {
     pub current_module: ModuleRef,
     root_module: ModuleRef,
     mode: Vec<Mode>,
+    input: Input,
+    output: Output,
+    include_paths: Vec<PathBuf>,
+    args: Vec<String>,
+    sandbox: Option<Sandbox>,
+    hooks: Vec<Rc<dyn CallHook>>,
+    activations: Vec<Activation>,
+    coverage: Option<Coverage>,
 }
 
 /// API
@@ -40,7 +61,10 @@ impl State {
     pub fn new() -> Self {
         let root_module = ModuleRef::new();
         State {
-            input_tokens: VecDeque::new(),
+            input_sources: vec![],
+            last_span: None,
+            docs: vec![],
+            finished_doc: None,
             stack: vec![],
             frames: vec![],
             current_module: root_module.clone(),
@@ -48,13 +72,24 @@ impl State {
             mode: vec![],
             scopes: vec![],
             root_module,
+            input: Input::default(),
+            output: Output::stdout(),
+            include_paths: vec![],
+            args: vec![],
+            sandbox: None,
+            hooks: vec![],
+            activations: vec![],
+            coverage: None,
         }
     }
 
     /// create new state that shares modules with the current state
     pub fn substate(&self) -> Self {
         State {
-            input_tokens: VecDeque::new(),
+            input_sources: vec![],
+            last_span: None,
+            docs: vec![],
+            finished_doc: None,
             stack: vec![],
             frames: vec![],
             current_module: self.current_module.clone(),
@@ -62,32 +97,351 @@ impl State {
             mode: vec![],
             scopes: vec![],
             root_module: self.root_module.clone(),
+            input: self.input.clone(),
+            output: self.output.clone(),
+            include_paths: self.include_paths.clone(),
+            args: self.args.clone(),
+            sandbox: self.sandbox.clone(),
+            hooks: vec![],
+            activations: vec![],
+            coverage: self.coverage.clone(),
         }
     }
 
+    /// Where input words read from
+    pub fn input(&self) -> &Input {
+        &self.input
+    }
+
+    /// Replace the input source, returning the previous one.
+    pub fn set_input(&mut self, input: Input) -> Input {
+        std::mem::replace(&mut self.input, input)
+    }
+
+    /// Where printing words write to
+    pub fn output(&self) -> &Output {
+        &self.output
+    }
+
+    /// Redirect printed output, returning the previous output.
+    pub fn set_output(&mut self, output: Output) -> Output {
+        std::mem::replace(&mut self.output, output)
+    }
+
+    /// Installed call hooks, in the order they were added
+    pub fn hooks(&self) -> &[Rc<dyn CallHook>] {
+        &self.hooks
+    }
+
+    /// Install a hook that observes all word calls, in addition to the hooks already installed.
+    pub fn add_hook(&mut self, hook: Rc<dyn CallHook>) {
+        self.hooks.push(hook);
+    }
+
+    /// Uninstall a hook. Returns false if it was not installed.
+    pub fn remove_hook(&mut self, hook: &Rc<dyn CallHook>) -> bool {
+        let len = self.hooks.len();
+        self.hooks.retain(|installed| !Rc::ptr_eq(installed, hook));
+        self.hooks.len() != len
+    }
+
+    /// The first installed hook of type `T`
+    pub fn find_hook<T: 'static>(&self) -> Option<&T> {
+        self.hooks
+            .iter()
+            .find_map(|hook| hook.as_any().downcast_ref::<T>())
+    }
+
+    /// Words currently executing, innermost last. Only tracked while a hook is installed.
+    pub fn activations(&self) -> &[Activation] {
+        &self.activations
+    }
+
+    pub fn coverage(&self) -> Option<&Coverage> {
+        self.coverage.as_ref()
+    }
+
+    /// Record coverage of code compiled and executed from now on, returning the previous recorder.
+    /// Substates share the recorder.
+    pub fn set_coverage(&mut self, coverage: Option<Coverage>) -> Option<Coverage> {
+        std::mem::replace(&mut self.coverage, coverage)
+    }
+
+    /// Call a word, notifying the hooks. Hooks are notified before the call in the order they
+    /// were added, and after the call in reverse order.
+    pub(crate) fn call_hooked(&mut self, word: &WordId) -> Result<()> {
+        // hooks added or removed during the call only see later calls
+        let hooks = self.hooks.clone();
+        for (i, hook) in hooks.iter().enumerate() {
+            if let Err(e) = hook.before_call(self, word) {
+                // hooks that saw the call starting also see it ending
+                for hook in hooks[..i].iter().rev() {
+                    hook.after_call(self, word).ok();
+                }
+                return Err(e);
+            }
+        }
+
+        self.activations.push(Activation {
+            word: word.clone(),
+            position: 0,
+        });
+        let mut result = word.word.inner().call(self);
+        self.activations.pop();
+
+        for hook in hooks.iter().rev() {
+            // an error of the word takes precedence over one of a hook
+            let after = hook.after_call(self, word);
+            result = result.and(after);
+        }
+        result
+    }
+
+    /// Note which item of a list is executing, if the list is the body of the innermost active word.
+    pub(crate) fn set_position(&mut self, list: &[Object], position: usize) {
+        if let Some(top) = self.activations.last_mut() {
+            if top
+                .word
+                .body()
+                .is_some_and(|body| std::ptr::eq(body.as_ptr(), list.as_ptr()))
+            {
+                top.position = position;
+            }
+        }
+    }
+
+    /// Create a new state that shares modules with the current state, but may only access what
+    /// the sandbox allows. Words are defined in a module of the sandbox's own, which sees the
+    /// root module but is not visible from it.
+    pub fn sandboxed(&self, mut sandbox: Sandbox) -> Self {
+        let mut state = self.substate();
+        let module = state
+            .root_module
+            .new_private_child(SANDBOX_MODULE.to_string());
+        sandbox.set_home(module.clone());
+        state.current_module = module;
+        state.sandbox = Some(sandbox);
+        state
+    }
+
+    pub fn sandbox(&self) -> Option<&Sandbox> {
+        self.sandbox.as_ref()
+    }
+
+    /// Fail with `AccessDenied` if the sandbox does not allow the word.
+    pub fn check_word(&self, entry: &Entry) -> Result<()> {
+        match self.sandbox {
+            Some(ref sandbox) if !sandbox.allows_word(entry) => {
+                Err(ErrorKind::AccessDenied(entry.qualified_name()).into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Fail with `AccessDenied` if the sandbox does not allow the module.
+    pub fn check_module(&self, module: &ModuleRef) -> Result<()> {
+        match self.sandbox {
+            Some(ref sandbox) if !sandbox.allows_module(&module.path()) => {
+                Err(ErrorKind::AccessDenied(format!(":{}", module.path())).into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Look up a word visible from the current module, or by its qualified name `:path:name`.
+    pub fn lookup(&self, name: &str) -> Result<Option<WordId>> {
+        let entry = match self.current_module.lookup(name) {
+            Some(entry) => Some(entry),
+            None => self.lookup_qualified(name)?,
+        };
+        match entry {
+            Some(entry) => {
+                self.check_word(&entry)?;
+                Ok(Some(entry))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Look up a word by the absolute path printed by `Entry::qualified_name`.
+    fn lookup_qualified(&self, name: &str) -> Result<Option<WordId>> {
+        let mut split = name.rsplitn(2, ':');
+        let word = split.next().unwrap_or("");
+        let path = match split.next().and_then(|path| path.strip_prefix(':')) {
+            Some(path) if !word.is_empty() => path,
+            _ => return Ok(None),
+        };
+
+        let module = if path.is_empty() {
+            Some(self.root_module.clone())
+        } else {
+            self.root_module.access_path(path)
+        };
+        match module {
+            Some(module) => {
+                self.check_module(&module)?;
+                Ok(module.local_lookup(word))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Add a directory to search for files loaded with `include`.
+    pub fn add_include_path(&mut self, path: impl Into<PathBuf>) {
+        self.include_paths.push(path.into())
+    }
+
+    /// Find a file by name. Relative names are searched for next to the file being run, as given
+    /// and then in the include paths.
+    pub fn find_include(&self, name: &str) -> Option<PathBuf> {
+        let path = Path::new(name);
+        if path.is_absolute() {
+            return Some(path.to_path_buf()).filter(|path| path.is_file());
+        }
+        let including_dir = self
+            .last_span
+            .as_ref()
+            .filter(|span| !span.source.name.starts_with('<'))
+            .and_then(|span| Path::new(&span.source.name).parent().map(Path::to_path_buf));
+        including_dir
+            .iter()
+            .map(|dir| dir.join(path))
+            .chain(Some(path.to_path_buf()))
+            .chain(self.include_paths.iter().map(|dir| dir.join(path)))
+            .find(|path| path.is_file())
+    }
+
+    /// Run the file found by `find_include`.
+    pub fn include(&mut self, name: &str) -> Result<()> {
+        if let Some(ref sandbox) = self.sandbox {
+            if !sandbox.allows_include() {
+                return Err(ErrorKind::AccessDenied(format!("INCLUDE {}", name)).into());
+            }
+        }
+        let path = self
+            .find_include(name)
+            .ok_or_else(|| ErrorKind::FileNotFound(name.to_string()))?;
+        let code = std::fs::read_to_string(&path)
+            .map_err(|e| ErrorKind::IoError(path.display().to_string(), e.to_string()))?;
+        self.run_source(Source::new(path.to_string_lossy(), code))
+    }
+
+    /// Command line arguments passed on to the program
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    pub fn set_args(&mut self, args: Vec<String>) {
+        self.args = args;
+    }
+
     pub fn current_mode(&self) -> Mode {
         self.mode.last().cloned().unwrap_or_else(|| Mode::default())
     }
 
     pub fn run(&mut self, input: &str) -> Result<()> {
-        self.input_tokens
-            .extend(tokenize(input).map(str::to_string));
+        self.run_source(Source::new("<input>", input))
+    }
+
+    /// Run code and remember where it came from, so that definitions know their location.
+    ///
+    /// The code is read from its own input source, so calling this from within a running word
+    /// neither sees nor consumes the remaining tokens of the outer code.
+    pub fn run_source(&mut self, source: Source) -> Result<()> {
+        let source = Rc::new(source);
+        self.input_sources.push(
+            tokenize_with_offsets(&source.text)
+                .map(|(offset, token)| Token {
+                    text: token.to_string(),
+                    span: Span::new(source.clone(), offset, offset + token.len()),
+                })
+                .collect(),
+        );
 
+        let result = self.run_tokens();
+        self.input_sources.pop();
+        result
+    }
+
+    fn run_tokens(&mut self) -> Result<()> {
         while let Some(token) = self.next_token() {
-            match self.parse_token(&token) {
-                Ok(_) => {}
-                err @ Err(_) => {
-                    self.input_tokens.clear();
-                    return err
-                }
-            }
+            self.parse_token(&token)?;
         }
-
         Ok(())
     }
 
+    /// Read the next token from the innermost input source.
     pub fn next_token(&mut self) -> Option<String> {
-        self.input_tokens.pop_front()
+        let token = self.input_sources.last_mut()?.pop_front()?;
+        self.last_span = Some(token.span);
+        Some(token.text)
+    }
+
+    /// Location of the token most recently returned by `next_token`
+    pub fn last_token_span(&self) -> Option<&Span> {
+        self.last_span.as_ref()
+    }
+
+    /// Span from `start` up to and including the most recently read token.
+    pub fn span_since(&self, start: Option<Span>) -> Option<Span> {
+        match (start, &self.last_span) {
+            (Some(start), Some(end)) if Rc::ptr_eq(&start.source, &end.source) => {
+                Some(start.to(end))
+            }
+            _ => None,
+        }
+    }
+
+    /// Parse a `(( ... ))` doc comment, if the input continues with one.
+    pub fn parse_doc_comment(&mut self) -> Result<Option<String>> {
+        let tokens = match self.input_sources.last() {
+            Some(tokens) => tokens,
+            None => return Ok(None),
+        };
+        let start = match (tokens.front(), tokens.get(1)) {
+            (Some(a), Some(b)) if a.text == "(" && b.text == "(" && a.span.end == b.span.start => {
+                b.span.clone()
+            }
+            _ => return Ok(None),
+        };
+
+        self.next_token();
+        self.next_token();
+
+        let mut end = start.end;
+        let mut nesting = 2;
+        while nesting > 0 {
+            match self.next_token().as_deref() {
+                None => return Err(ErrorKind::EndOfInput.into()),
+                Some("(") => nesting += 1,
+                Some(")") => nesting -= 1,
+                Some(_) => {}
+            }
+            if nesting == 1 {
+                end = self.last_span.as_ref().map_or(end, |span| span.start);
+            }
+        }
+
+        let text = &start.source.text[start.end..end];
+        let lines: Vec<_> = text.trim().lines().map(str::trim).collect();
+        Ok(Some(lines.join("\n")))
+    }
+
+    /// Remember a doc string for the code currently being compiled.
+    pub fn set_pending_doc(&mut self, doc: String) -> Result<()> {
+        match self.docs.last_mut() {
+            Some(slot) => {
+                *slot = Some(doc);
+                Ok(())
+            }
+            None => Err(ErrorKind::RuntimeError("DOC outside of a definition".to_string()).into()),
+        }
+    }
+
+    /// Take the doc string set with `set_pending_doc` while compiling the code that was compiled
+    /// last. Doc strings of code that failed to compile are discarded.
+    pub fn take_pending_doc(&mut self) -> Option<String> {
+        self.finished_doc.take()
     }
 
     pub fn parse_until(&mut self, delimiter: &str) -> Result<()> {
@@ -105,62 +459,71 @@ impl State {
         // todo: i don't know yet which takes up more time - parsing or lookup...
         //       so we always do them both now, and future profiling will show which to do first in the future
         let literal = self.factory.parse(&token);
-        let word = self.current_module.lookup(&token);
+        let word = self.lookup(&token)?;
         let mode = self.mode.last().unwrap_or(&Mode::Eval);
         match (mode, literal, word) {
             (_, None, None) => return Err(ErrorKind::UnknownWord(token.to_string()).into()),
             (_, Some(_), Some(_)) => return Err(ErrorKind::AmbiguousWord(token.to_string()).into()),
             (Mode::Eval, Some(obj), None) => self.push(obj)?,
             (Mode::Compile, Some(obj), None) => self.top_mut()?.as_vec_mut()?.push(obj),
-            (Mode::Eval, None, Some(entry)) => entry.word.inner().call(self)?,
+            (Mode::Eval, None, Some(entry)) => Object::Word(entry).call(self)?,
             (Mode::Compile, None, Some(entry)) => match &entry.word {
                 Word::Word(_) => {
                     //let op = Opcode::call_word(entry.clone());
                     let op = Object::Word(entry);
                     self.top_mut()?.as_vec_mut()?.push(op);
                 }
-                Word::ParsingWord(obj) => obj.clone().call(self)?,
+                Word::ParsingWord(_) => Object::Word(entry).call(self)?,
             },
         }
         Ok(())
     }
 
+    /// Add a word to the current module.
+    pub fn add_entry(&mut self, mut entry: Entry) {
+        entry.module = self.current_module.path();
+        if let Some(ref coverage) = self.coverage {
+            coverage.add_word(&entry);
+        }
+        self.current_module.insert(entry.name.clone(), entry);
+    }
+
     pub fn add_native_word<S>(
         &mut self,
         name: S,
         stack_effect: impl IntoStackEffect,
+        doc: Option<&str>,
         func: impl Fn(&mut State) -> Result<()> + 'static,
     ) where
         ObjectFactory: StringManager<S>,
     {
         let name = self.factory.get_string(name);
-        self.current_module.insert(
-            name.clone(),
-            Entry {
+        self.add_entry(
+            Entry::new(
                 name,
-                word: Word::Word(Object::Function(Callable::new_const(func))),
-                source: None,
-                stack_effect: stack_effect.into_stack_effect(),
-            },
+                Word::Word(Object::Function(Callable::new_const(func))),
+                stack_effect.into_stack_effect(),
+            )
+            .with_doc(doc.map(str::to_string)),
         );
     }
 
     pub fn add_native_parse_word<S>(
         &mut self,
         name: S,
+        doc: Option<&str>,
         func: impl Fn(&mut State) -> Result<()> + 'static,
     ) where
         ObjectFactory: StringManager<S>,
     {
         let name = self.factory.get_string(name);
-        self.current_module.insert(
-            name.clone(),
-            Entry {
+        self.add_entry(
+            Entry::new(
                 name,
-                word: Word::ParsingWord(Object::Function(Callable::new_const(func))),
-                source: None,
-                stack_effect: StackEffect::new_mod("acc"),
-            },
+                Word::ParsingWord(Object::Function(Callable::new_const(func))),
+                StackEffect::new_mod("acc"),
+            )
+            .with_doc(doc.map(str::to_string)),
         );
     }
 
@@ -169,15 +532,11 @@ impl State {
         ObjectFactory: StringManager<S>,
     {
         let name = self.factory.get_string(name);
-        self.current_module.insert(
-            name.clone(),
-            Entry {
-                name,
-                source: None,
-                word: Word::Word(obj),
-                stack_effect: stack_effect.into_stack_effect(),
-            },
-        );
+        self.add_entry(Entry::new(
+            name,
+            Word::Word(obj),
+            stack_effect.into_stack_effect(),
+        ));
     }
 
     pub fn add_compound_parse_word<S>(&mut self, name: S, obj: Object)
@@ -185,53 +544,53 @@ impl State {
         ObjectFactory: StringManager<S>,
     {
         let name = self.factory.get_string(name);
-        self.current_module.insert(
-            name.clone(),
-            Entry {
-                name,
-                source: None,
-                word: Word::ParsingWord(obj),
-                stack_effect: StackEffect::new_mod("acc"),
-            },
-        );
+        self.add_entry(Entry::new(
+            name,
+            Word::ParsingWord(obj),
+            StackEffect::new_mod("acc"),
+        ));
     }
 
-    pub fn format_word(&self, name: &str) {
+    pub fn format_word(&self, name: &str) -> Result<()> {
         let entry = self.current_module.lookup(name);
         match entry {
-            None => println!("{:>20}  undefined!", name),
+            None => writeln!(self.output, "{:>20}  undefined!", name)?,
             Some(entry) => match entry.word.inner() {
                 Object::Function(ca) => {
                     let func = match entry.source {
                         None => format!("<{:?}>", ca),
                         Some(ref byte_code) => format!("{}", byte_code),
                     };
-                    println!(
+                    writeln!(
+                        self.output,
                         "{:>20}   {:50}   {}",
                         entry.name,
                         format!("({})", entry.stack_effect),
                         func
-                    )
+                    )?
                 }
                 Object::List(list) => {
-                    println!(
+                    writeln!(
+                        self.output,
                         "{:>20}   {:50}   {:?}",
                         entry.name,
                         format!("({})", entry.stack_effect),
                         list
-                    );
+                    )?;
                 }
-                _ => println!("{:>20}  invalid word", name),
+                _ => writeln!(self.output, "{:>20}  invalid word", name)?,
             },
         }
+        Ok(())
     }
 
-    pub fn print_dictionary(&self) {
+    pub fn print_dictionary(&self) -> Result<()> {
         let mut words = self.current_module.keys();
         words.sort();
         for word in words {
-            self.format_word(word.borrow());
+            self.format_word(word.borrow())?;
         }
+        Ok(())
     }
 
     pub fn clear_stack(&mut self) {
@@ -290,15 +649,16 @@ impl State {
 
     pub fn compile<F: FnOnce(&mut Self) -> Result<()>>(&mut self, func: F) -> Result<()> {
         self.mode.push(Mode::Compile);
+        self.docs.push(None);
         self.push(Object::List(Rc::new(Vec::new()))).unwrap();
-        match func(self) {
-            Ok(_) => {
-                self.mode.pop();
-                Ok(())
-            },
+        let result = func(self);
+        self.mode.pop();
+        self.finished_doc = self.docs.pop().flatten();
+        match result {
+            Ok(_) => Ok(()),
             err @ Err(_) => {
+                self.finished_doc = None;
                 self.pop().unwrap();
-                self.mode.pop();
                 err
             }
         }
@@ -369,9 +729,41 @@ impl State {
         &self.root_module
     }
 
-    pub fn new_mod(&mut self, name: String) -> Result<()> {
+    /// Find a module by path. Paths starting with `:` are relative to the root module, all
+    /// others to the current module. The empty path denotes the current module itself.
+    pub fn lookup_module(&self, path: &str) -> Result<ModuleRef> {
+        let (relative, path) = match path.strip_prefix(':') {
+            Some(path) => (&self.root_module, path),
+            None => (&self.current_module, path),
+        };
+
+        let path = path.trim_end_matches(':');
+        let module = if path.is_empty() {
+            relative.clone()
+        } else {
+            relative.access_path(path).ok_or(ErrorKind::PathError)?
+        };
+
+        self.check_module(&module)?;
+        Ok(module)
+    }
+
+    /// Create a submodule of the current module, as far as the sandbox allows.
+    fn create_submodule(&mut self, name: String) -> Result<ModuleRef> {
+        // replacing an existing module would affect everyone sharing it
+        if self.sandbox.is_some() && self.current_module.access_path(&name).is_some() {
+            return Err(ErrorKind::AccessDenied(format!("MODULE {}", name)).into());
+        }
+
         let newmod = self.current_module.new_submodule(name);
-        self.current_module = newmod;
+        if let Some(ref mut sandbox) = self.sandbox {
+            sandbox.add_own_module(newmod.path());
+        }
+        Ok(newmod)
+    }
+
+    pub fn new_mod(&mut self, name: String) -> Result<()> {
+        self.current_module = self.create_submodule(name)?;
 
         // We define the END-MODULE word only in new submodules.
         // This prevents accidentally ending the root module.
@@ -379,11 +771,15 @@ impl State {
         // from another module and cause havoc in the root. For now,
         // we simply panic in this case. Ignoring or warning might
         // be fine too...
-        self.add_native_parse_word("END-MODULE", |state| {
-            state
-                .exit_mod()
-                .ok_or_else(|| panic!("Error: attempt to end root module"))
-        });
+        self.add_native_parse_word(
+            "END-MODULE",
+            Some("End the current module definition."),
+            |state| {
+                state
+                    .exit_mod()
+                    .ok_or_else(|| panic!("Error: attempt to end root module"))
+            },
+        );
 
         Ok(())
     }
@@ -393,4 +789,30 @@ impl State {
             .parent()
             .map(|parent| self.current_module = parent)
     }
+
+    /// Call `func` with the module at `path`, relative to the root module, as current module.
+    /// Missing modules are created. The current module is restored afterwards.
+    pub fn with_module<T>(
+        &mut self,
+        path: &str,
+        func: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        let previous = std::mem::replace(&mut self.current_module, self.root_module.clone());
+        let result = self.enter_path(path).and_then(|_| func(self));
+        self.current_module = previous;
+        result
+    }
+
+    fn enter_path(&mut self, path: &str) -> Result<()> {
+        for name in path.split(':').filter(|name| !name.is_empty()) {
+            match self.current_module.access_path(name) {
+                Some(module) => {
+                    self.check_module(&module)?;
+                    self.current_module = module;
+                }
+                None => self.current_module = self.create_submodule(name.to_string())?,
+            }
+        }
+        Ok(())
+    }
 }
@@ -1,8 +1,19 @@
 use std::fmt::Debug;
+use std::fs;
+use std::path::Path;
 
-use crate::objects::Object;
+use crate::objects::prelude::*;
+use crate::output::Output;
+use crate::source::Source;
 use crate::State;
 
+/// Set this environment variable to make `run_golden` write `.expected` files instead of
+/// comparing against them.
+pub const BLESS_VAR: &str = "FORTHEN_BLESS";
+
+/// Placeholder in `.expected` files that matches any text within a line
+pub const WILDCARD: &str = "[..]";
+
 impl State {
     pub fn assert_stack<T>(&self, expected: &[T])
     where
@@ -54,3 +65,165 @@ impl State {
         }
     }
 }
+
+/// Run a script in a new state and compare its output, final stack and error against the
+/// sibling `.expected` file (`hello.fn` is compared against `hello.expected`).
+///
+/// Panics if they differ. If the environment variable `FORTHEN_BLESS` is set, an `.expected`
+/// file that differs is written instead. Files that still match are left alone, so their `[..]`
+/// wildcards survive blessing.
+pub fn run_golden(path: impl AsRef<Path>) {
+    run_golden_with(&mut State::new(), path)
+}
+
+/// Like `run_golden`, but run the script in a prepared state, e.g. one with the standard library
+/// loaded.
+pub fn run_golden_with(state: &mut State, path: impl AsRef<Path>) {
+    let path = path.as_ref();
+    let expected_path = path.with_extension("expected");
+    let actual = transcript(state, path);
+
+    if std::env::var_os(BLESS_VAR).is_some() {
+        bless(&expected_path, &actual);
+        return;
+    }
+
+    let expected = fs::read_to_string(&expected_path).unwrap_or_else(|e| {
+        panic!(
+            "Cannot read {}: {}\nRun with {}=1 to create it.",
+            expected_path.display(),
+            e,
+            BLESS_VAR
+        )
+    });
+    if !matches_golden(&expected, &actual) {
+        panic!(
+            "Output of {} does not match {}\n--- expected\n{}--- actual\n{}\nRun with {}=1 to update it.",
+            path.display(),
+            expected_path.display(),
+            expected,
+            actual,
+            BLESS_VAR
+        );
+    }
+}
+
+/// Write the transcript to the `.expected` file, unless the file already matches it.
+fn bless(expected_path: &Path, actual: &str) {
+    let expected = fs::read_to_string(expected_path);
+    if !expected.is_ok_and(|expected| matches_golden(&expected, actual)) {
+        fs::write(expected_path, actual).unwrap();
+    }
+}
+
+/// Output, stack and error of a script, in the format of `.expected` files
+fn transcript(state: &mut State, path: &Path) -> String {
+    let code = fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("Cannot read {}: {}", path.display(), e));
+    let (output, buffer) = Output::capture();
+    let old_output = state.set_output(output);
+    let result = state.run_source(Source::new(path.to_string_lossy(), code));
+    state.set_output(old_output);
+
+    let mut text = buffer.contents();
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text += "--- stack\n";
+    for item in &state.stack {
+        text += &item.repr_sys();
+        text.push('\n');
+    }
+    if let Err(e) = result {
+        text += &format!("--- error\n{}\n", e);
+    }
+    text
+}
+
+/// Compare line by line, where `[..]` in an expected line matches any text.
+fn matches_golden(expected: &str, actual: &str) -> bool {
+    let expected: Vec<_> = expected.lines().collect();
+    let actual: Vec<_> = actual.lines().collect();
+    expected.len() == actual.len()
+        && expected
+            .iter()
+            .zip(&actual)
+            .all(|(e, a)| matches_line(e, a))
+}
+
+fn matches_line(expected: &str, actual: &str) -> bool {
+    let mut parts = expected.split(WILDCARD);
+    let first = parts.next().unwrap_or("");
+    let mut rest = match actual.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    let parts: Vec<_> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        if i == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcards() {
+        assert!(matches_line("abc", "abc"));
+        assert!(!matches_line("abc", "abcd"));
+        assert!(matches_line("a[..]", "abcd"));
+        assert!(matches_line("[..]d", "abcd"));
+        assert!(matches_line("a[..]c[..]e", "abcde"));
+        assert!(!matches_line("a[..]c[..]e", "abde"));
+        assert!(!matches_line("ab[..]bc", "abc"));
+        assert!(matches_golden("x\n[..] ms\n", "x\n3.5 ms\n"));
+        assert!(!matches_golden("x\n", "x\ny\n"));
+    }
+
+    #[test]
+    fn golden_transcript() {
+        let dir = std::env::temp_dir().join(format!("forthen-golden-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("script.fn");
+        fs::write(&script, "1 \"two\" three").unwrap();
+        fs::write(
+            dir.join("script.expected"),
+            "--- stack\n1\n\"[..]\"\n--- error\nUnkown Word: three\n",
+        )
+        .unwrap();
+
+        let state = &mut State::new();
+        assert_eq!(
+            transcript(state, &script),
+            "--- stack\n1\n\"two\"\n--- error\nUnkown Word: three\n"
+        );
+        run_golden(&script);
+
+        // blessing keeps wildcards that still match
+        let expected_path = dir.join("script.expected");
+        bless(
+            &expected_path,
+            "--- stack\n1\n\"three\"\n--- error\nUnkown Word: three\n",
+        );
+        assert!(fs::read_to_string(&expected_path).unwrap().contains("[..]"));
+        bless(&expected_path, "--- stack\n1\n");
+        assert_eq!(
+            fs::read_to_string(&expected_path).unwrap(),
+            "--- stack\n1\n"
+        );
+
+        fs::write(&script, "2").unwrap();
+        let mismatch = std::panic::catch_unwind(|| run_golden(&script));
+        fs::remove_dir_all(&dir).unwrap();
+        // blessing accepts any output
+        assert_eq!(mismatch.is_err(), std::env::var_os(BLESS_VAR).is_none());
+    }
+}
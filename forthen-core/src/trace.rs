@@ -0,0 +1,203 @@
+use crate::dictionary::{Entry, WordId};
+use crate::errors::*;
+use crate::hook::CallHook;
+use crate::objects::prelude::*;
+use crate::output::Output;
+use crate::state::State;
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::rc::Rc;
+
+/// Number of stack items shown in each trace line
+const STACK_ITEMS: usize = 8;
+
+/// Call hook that logs entry and exit of words, indented by call depth, together with the stack.
+///
+/// Only calls of selected words are logged: words given by qualified name, all words of given
+/// modules, or everything. The log goes to the output of the state unless another output is set.
+/// Clones share their settings.
+#[derive(Clone)]
+pub struct Tracer(Rc<Settings>);
+
+struct Settings {
+    output: RefCell<Option<Output>>,
+    all: RefCell<bool>,
+    words: RefCell<BTreeSet<String>>,
+    modules: RefCell<BTreeSet<String>>,
+}
+
+impl Tracer {
+    pub fn new() -> Self {
+        Tracer(Rc::new(Settings {
+            output: RefCell::new(None),
+            all: RefCell::new(false),
+            words: RefCell::new(BTreeSet::new()),
+            modules: RefCell::new(BTreeSet::new()),
+        }))
+    }
+
+    /// Write the trace somewhere else.
+    pub fn set_output(&self, output: Output) {
+        self.0.output.replace(Some(output));
+    }
+
+    pub fn trace_all(&self) {
+        self.0.all.replace(true);
+    }
+
+    /// Trace a word, given by its qualified name (see `Entry::qualified_name`).
+    pub fn trace_word(&self, qualified_name: &str) {
+        self.0.words.borrow_mut().insert(qualified_name.to_string());
+    }
+
+    /// Trace all words defined in a module.
+    pub fn trace_module(&self, path: &str) {
+        self.0
+            .modules
+            .borrow_mut()
+            .insert(normalize(path).to_string());
+    }
+
+    /// Stop tracing a word or module. Returns false if it was not traced.
+    pub fn untrace(&self, name: &str) -> bool {
+        self.0.words.borrow_mut().remove(name)
+            || self.0.modules.borrow_mut().remove(normalize(name))
+    }
+
+    pub fn untrace_all(&self) {
+        self.0.all.replace(false);
+        self.0.words.borrow_mut().clear();
+        self.0.modules.borrow_mut().clear();
+    }
+
+    /// True if any word is traced.
+    pub fn is_active(&self) -> bool {
+        *self.0.all.borrow()
+            || !self.0.words.borrow().is_empty()
+            || !self.0.modules.borrow().is_empty()
+    }
+
+    fn is_traced(&self, entry: &Entry) -> bool {
+        *self.0.all.borrow()
+            || self.0.modules.borrow().contains(&entry.module)
+            || self.0.words.borrow().contains(&entry.qualified_name())
+    }
+
+    fn log(&self, state: &State, marker: &str, word: &Entry) -> Result<()> {
+        let indent = "  ".repeat(state.activations().len());
+        let parsing = if word.is_parsing() { " (parsing)" } else { "" };
+        let output = self.0.output.borrow();
+        writeln!(
+            output.as_ref().unwrap_or_else(|| state.output()),
+            "{}{} {}{}  {}",
+            indent,
+            marker,
+            word.name,
+            parsing,
+            stack_text(&state.stack)
+        )?;
+        Ok(())
+    }
+}
+
+impl Default for Tracer {
+    fn default() -> Self {
+        Tracer::new()
+    }
+}
+
+impl CallHook for Tracer {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn before_call(&self, state: &mut State, word: &WordId) -> Result<()> {
+        if self.is_traced(word) {
+            self.log(state, ">", word)?;
+        }
+        Ok(())
+    }
+
+    fn after_call(&self, state: &mut State, word: &WordId) -> Result<()> {
+        if self.is_traced(word) {
+            self.log(state, "<", word)?;
+        }
+        Ok(())
+    }
+}
+
+/// The top of the stack. Uses `repr_sys`, so that no user code runs while tracing.
+fn stack_text(stack: &[Object]) -> String {
+    let start = stack.len().saturating_sub(STACK_ITEMS);
+    let items: Vec<_> = stack[start..].iter().map(|obj| obj.repr_sys()).collect();
+    if start > 0 {
+        format!("[.., {}]", items.join(", "))
+    } else {
+        format!("[{}]", items.join(", "))
+    }
+}
+
+fn normalize(path: &str) -> &str {
+    path.trim_start_matches(':').trim_end_matches(':')
+}
+
+/// Tracing
+impl State {
+    /// The installed tracer. If there is none yet, a new tracer is installed first.
+    pub fn tracer(&mut self) -> Tracer {
+        if let Some(tracer) = self.find_hook::<Tracer>() {
+            return tracer.clone();
+        }
+        let tracer = Tracer::new();
+        self.add_hook(Rc::new(tracer.clone()));
+        tracer
+    }
+
+    /// Uninstall the tracer if it does not trace anything and has no output of its own.
+    pub fn remove_idle_tracer(&mut self) {
+        let idle = self.hooks().iter().find(|hook| {
+            matches!(hook.as_any().downcast_ref::<Tracer>(),
+                     Some(tracer) if !tracer.is_active() && tracer.0.output.borrow().is_none())
+        });
+        if let Some(hook) = idle.cloned() {
+            self.remove_hook(&hook);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace_calls() {
+        let state = &mut State::new();
+        state.new_mod("inner".to_string()).unwrap();
+        state.add_native_word("one", "( -- x)", None, |state| state.push(1));
+        state.exit_mod().unwrap();
+        let one = state.lookup_module(":inner").unwrap().lookup("one");
+        let one = Object::Word(one.unwrap());
+        let body = vec![one.clone(), Object::I32(2), one];
+        state.add_compound_word("two", "( -- x y z)", Object::List(Rc::new(body)));
+
+        let (output, buffer) = Output::capture();
+        let tracer = state.tracer();
+        tracer.set_output(output);
+        tracer.trace_word("::two");
+        tracer.trace_module(":inner:");
+        state.run("0 two").unwrap();
+
+        assert_eq!(
+            buffer.contents(),
+            "> two  [0]\n  > one  [0]\n  < one  [0, 1]\n  > one  [0, 1, 2]\n  < one  [0, 1, 2, 1]\n< two  [0, 1, 2, 1]\n"
+        );
+
+        assert!(tracer.untrace("::two"));
+        assert!(tracer.untrace("inner"));
+        assert!(!tracer.untrace("inner"));
+        // the tracer keeps its own output
+        state.remove_idle_tracer();
+        assert_eq!(state.hooks().len(), 1);
+    }
+}
@@ -0,0 +1,158 @@
+pub const USAGE: &str = "\
+Usage: forthen [options] [script ...] [-- args ...]
+       forthen doc [--html] [-o outdir] [script ...]
+       forthen test [dir|file ...]
+
+Scripts and -e code run in the order given. Without any, an interactive
+session starts. `forthen test` runs the tests in all *_test.fn files of the
+given directories.
+
+Options:
+  -e CODE     run CODE
+  -           read a script from stdin
+  -i          start an interactive session after running the scripts
+  -I DIR      search DIR for files loaded with INCLUDE
+  --no-std    do not load the standard library
+  --trace     log every word call with the stack before and after
+  --trace-file FILE
+              write the trace to FILE instead of stdout
+  --profile   print how much time the scripts spent in each word
+  --profile-folded FILE
+              write the profile of the scripts to FILE in the folded format
+              of flame graph tools
+  --coverage FILE
+              write which words and quotations of the scripts ran to FILE
+              in lcov format
+  -h, --help  print this help
+  --          pass all remaining arguments to the program";
+
+/// Something to run, in command line order
+#[derive(Debug, PartialEq)]
+pub enum Script {
+    File(String),
+    Stdin,
+    Code(String),
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Options {
+    pub scripts: Vec<Script>,
+    pub interactive: bool,
+    pub no_std: bool,
+    pub trace: bool,
+    pub trace_file: Option<String>,
+    pub profile: bool,
+    pub profile_folded: Option<String>,
+    pub coverage: Option<String>,
+    pub include_paths: Vec<String>,
+    pub args: Vec<String>,
+    pub help: bool,
+}
+
+impl Options {
+    pub fn parse(args: &[&str]) -> Result<Self, String> {
+        let mut options = Options::default();
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match *arg {
+                "-e" => {
+                    let code = args.next().ok_or("Expected code after -e.")?;
+                    options.scripts.push(Script::Code(code.to_string()));
+                }
+                "-I" => {
+                    let dir = args.next().ok_or("Expected directory after -I.")?;
+                    options.include_paths.push(dir.to_string());
+                }
+                "-" => options.scripts.push(Script::Stdin),
+                "-i" => options.interactive = true,
+                "--no-std" => options.no_std = true,
+                "--trace" => options.trace = true,
+                "--profile" => options.profile = true,
+                "--profile-folded" => {
+                    let file = args
+                        .next()
+                        .ok_or("Expected file name after --profile-folded.")?;
+                    options.profile_folded = Some(file.to_string());
+                }
+                "--coverage" => {
+                    let file = args.next().ok_or("Expected file name after --coverage.")?;
+                    options.coverage = Some(file.to_string());
+                }
+                "--trace-file" => {
+                    let file = args
+                        .next()
+                        .ok_or("Expected file name after --trace-file.")?;
+                    options.trace_file = Some(file.to_string());
+                }
+                "-h" | "--help" => options.help = true,
+                "--" => {
+                    options.args = args.map(|arg| arg.to_string()).collect();
+                    break;
+                }
+                opt if opt.starts_with('-') => return Err(format!("Unknown option {}.", opt)),
+                file => options.scripts.push(Script::File(file.to_string())),
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// Profile running the scripts?
+    pub fn profiling(&self) -> bool {
+        self.profile || self.profile_folded.is_some()
+    }
+
+    /// Start an interactive session after running the scripts?
+    pub fn repl(&self) -> bool {
+        self.interactive || self.scripts.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_arguments() {
+        assert_eq!(Options::parse(&[]).unwrap(), Options::default());
+        assert!(Options::parse(&[]).unwrap().repl());
+
+        let options = Options::parse(&[
+            "--no-std", "-I", "lib", "a.fn", "-e", "1 2 +", "-", "b.fn", "--", "-x", "b.fn",
+        ])
+        .unwrap();
+        assert_eq!(
+            options.scripts,
+            vec![
+                Script::File("a.fn".to_string()),
+                Script::Code("1 2 +".to_string()),
+                Script::Stdin,
+                Script::File("b.fn".to_string()),
+            ]
+        );
+        assert!(options.no_std);
+        assert_eq!(options.include_paths, vec!["lib"]);
+        assert_eq!(options.args, vec!["-x", "b.fn"]);
+        assert!(!options.repl());
+
+        assert!(Options::parse(&["-i", "a.fn"]).unwrap().repl());
+
+        let options = Options::parse(&["--trace", "--trace-file", "t.log"]).unwrap();
+        assert!(options.trace);
+        assert_eq!(options.trace_file, Some("t.log".to_string()));
+
+        assert!(Options::parse(&["-e"]).is_err());
+        assert!(Options::parse(&["--trace-file"]).is_err());
+
+        assert!(!Options::parse(&["a.fn"]).unwrap().profiling());
+        assert!(Options::parse(&["--profile"]).unwrap().profiling());
+        let options = Options::parse(&["--profile-folded", "a.folded"]).unwrap();
+        assert!(options.profiling());
+        assert!(!options.profile);
+        let options = Options::parse(&["--coverage", "out.lcov", "a.fn"]).unwrap();
+        assert_eq!(options.coverage, Some("out.lcov".to_string()));
+        assert!(Options::parse(&["--coverage"]).is_err());
+        assert!(Options::parse(&["--bogus"]).is_err());
+    }
+}
@@ -0,0 +1,330 @@
+use crate::display::{repr, StackDisplay};
+use forthen_core::errors::*;
+use forthen_core::objects::prelude::*;
+use forthen_core::{CallHook, Input, Object, Output, State, WordId};
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+
+const HELP: &str = "\
+  s, step      stop at the next word call
+  n, next      stop at the next word call that is not inside this one
+  o, out       stop after returning from the current word
+  c, continue  run until the next breakpoint
+  b NAME       stop whenever NAME is called; without NAME list breakpoints
+  d NAME       remove the breakpoint on NAME
+  w, where     show the words being executed
+  q, quit      abort execution
+An empty line repeats the previous command.";
+
+/// When the debugger stops next
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Run {
+    /// before the next word call
+    Step,
+    /// before the next call at this depth or less
+    Over(usize),
+    /// before the next call at less than this depth
+    Out(usize),
+    /// only at breakpoints
+    Continue,
+}
+
+/// Interactive step debugger, installed as the call hook of a state
+///
+/// It stops before word calls, shows where execution is and reads commands from its input.
+pub struct Debugger {
+    input: Input,
+    output: Output,
+    display: RefCell<StackDisplay>,
+    run: RefCell<Run>,
+    breakpoints: RefCell<BTreeSet<String>>,
+    last_command: RefCell<String>,
+}
+
+impl Debugger {
+    pub fn new(input: Input, output: Output) -> Self {
+        Debugger {
+            input,
+            output,
+            display: RefCell::new(StackDisplay::default()),
+            run: RefCell::new(Run::Continue),
+            breakpoints: RefCell::new(BTreeSet::new()),
+            last_command: RefCell::new("step".to_string()),
+        }
+    }
+
+    /// Change how the stack is shown.
+    pub fn set_display(&self, display: StackDisplay) {
+        self.display.replace(display);
+    }
+
+    pub fn set_run(&self, run: Run) {
+        *self.run.borrow_mut() = run;
+    }
+
+    pub fn add_breakpoint(&self, name: &str) {
+        self.breakpoints.borrow_mut().insert(name.to_string());
+    }
+
+    pub fn remove_breakpoint(&self, name: &str) -> bool {
+        self.breakpoints.borrow_mut().remove(name)
+    }
+
+    pub fn breakpoints(&self) -> Vec<String> {
+        self.breakpoints.borrow().iter().cloned().collect()
+    }
+
+    fn should_stop(&self, depth: usize, word: &WordId) -> bool {
+        let run = *self.run.borrow();
+        let stepping = match run {
+            Run::Step => true,
+            Run::Over(d) => depth <= d,
+            Run::Out(d) => depth < d,
+            Run::Continue => false,
+        };
+        stepping || self.breakpoints.borrow().contains(word.name.as_str())
+    }
+
+    /// Show the word about to be called, the body it is called from, the stack and the locals.
+    fn show(&self, state: &State, word: &WordId) -> Result<()> {
+        writeln!(self.output, "-> {}  ({})", word.name, word.stack_effect)?;
+
+        match state.activations().last() {
+            Some(caller) => {
+                let body = match caller.word.body() {
+                    Some(body) => body
+                        .iter()
+                        .enumerate()
+                        .map(|(i, item)| {
+                            let name = item_name(item);
+                            if i == caller.position {
+                                format!(">{}<", name)
+                            } else {
+                                name
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    None => "<native>".to_string(),
+                };
+                writeln!(self.output, "   in {}: {}", caller.word.name, body)?;
+            }
+            None => writeln!(self.output, "   in <input>")?,
+        }
+
+        writeln!(
+            self.output,
+            "   stack: {}",
+            self.display.borrow().format(state)
+        )?;
+
+        let locals = state
+            .activations()
+            .last()
+            .and_then(|caller| caller.word.locals.as_ref());
+        if let Some(names) = locals {
+            if !names.is_empty() && state.frames.len() >= names.len() {
+                let values: Vec<_> = names
+                    .iter()
+                    .enumerate()
+                    .map(|(i, name)| {
+                        let value = &state.frames[state.frames.len() - 1 - i];
+                        format!("{} = {}", name, repr(value, state))
+                    })
+                    .collect();
+                writeln!(self.output, "   locals: {}", values.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn show_backtrace(&self, state: &State) -> Result<()> {
+        writeln!(self.output, "   <input>")?;
+        for act in state.activations() {
+            writeln!(self.output, "   {} at {}", act.word.name, act.position)?;
+        }
+        Ok(())
+    }
+
+    /// Read commands until one continues execution.
+    fn prompt(&self, state: &State, depth: usize) -> Result<()> {
+        loop {
+            write!(self.output, "debug> ")?;
+            self.output.flush()?;
+
+            let line = match self.input.read_line()? {
+                Some(line) => line.trim().to_string(),
+                None => {
+                    self.set_run(Run::Continue);
+                    return Ok(());
+                }
+            };
+            let line = if line.is_empty() {
+                self.last_command.borrow().clone()
+            } else {
+                self.last_command.replace(line.clone());
+                line
+            };
+
+            let mut words = line.split_whitespace();
+            let run = match (words.next().unwrap_or(""), words.next()) {
+                ("s", _) | ("step", _) => Run::Step,
+                ("n", _) | ("next", _) => Run::Over(depth),
+                ("o", _) | ("out", _) => Run::Out(depth),
+                ("c", _) | ("continue", _) => Run::Continue,
+                ("q", _) | ("quit", _) => {
+                    return Err(ErrorKind::RuntimeError("Debugging aborted".to_string()).into())
+                }
+                ("b", None) | ("break", None) => {
+                    writeln!(
+                        self.output,
+                        "   breakpoints: {}",
+                        self.breakpoints().join(" ")
+                    )?;
+                    continue;
+                }
+                ("b", Some(name)) | ("break", Some(name)) => {
+                    self.add_breakpoint(name);
+                    continue;
+                }
+                ("d", Some(name)) | ("delete", Some(name)) => {
+                    if !self.remove_breakpoint(name) {
+                        writeln!(self.output, "   no breakpoint on {}", name)?;
+                    }
+                    continue;
+                }
+                ("w", _) | ("where", _) => {
+                    self.show_backtrace(state)?;
+                    continue;
+                }
+                _ => {
+                    writeln!(self.output, "{}", HELP)?;
+                    continue;
+                }
+            };
+            self.set_run(run);
+            return Ok(());
+        }
+    }
+}
+
+impl CallHook for Debugger {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn before_call(&self, state: &mut State, word: &WordId) -> Result<()> {
+        let depth = state.activations().len();
+        if !self.should_stop(depth, word) {
+            return Ok(());
+        }
+        self.show(state, word)?;
+        self.prompt(state, depth)
+    }
+}
+
+fn item_name(item: &Object) -> String {
+    match item {
+        Object::Word(id) => id.name.to_string(),
+        _ => item.repr_sys(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forthen_std::stdlib;
+    use std::rc::Rc;
+
+    fn debug(state: &mut State, code: &str, run: Run, commands: &str) -> (Result<()>, String) {
+        let (output, buffer) = Output::capture();
+        let display = StackDisplay {
+            colors: false,
+            ..StackDisplay::default()
+        };
+        let debugger = Debugger::new(Input::from_string(commands), output);
+        debugger.set_display(display);
+        debugger.set_run(run);
+        debugger.add_breakpoint("square");
+
+        let hook: Rc<dyn CallHook> = Rc::new(debugger);
+        state.add_hook(hook.clone());
+        let result = state.run(code);
+        state.remove_hook(&hook);
+        (result, buffer.contents())
+    }
+
+    fn stops(output: &str) -> Vec<&str> {
+        output
+            .lines()
+            .filter_map(|line| line.strip_prefix("debug> -> ").or(line.strip_prefix("-> ")))
+            .collect()
+    }
+
+    #[test]
+    fn stepping() {
+        let state = &mut State::new();
+        stdlib(state).unwrap();
+        state.run("USE std:").unwrap();
+        state.run(": square ( x -- y ) dup * ;").unwrap();
+        state
+            .run(":: twice ( x -- y ) set x get x square square ;")
+            .unwrap();
+
+        let (result, output) = debug(state, "3 twice", Run::Step, "s\ns\n\nn\no\nc\n");
+        result.unwrap();
+        state.assert_stack(&[81]);
+        assert_eq!(
+            stops(&output),
+            vec![
+                "twice  (x -- y)",
+                "push_frame  (n -- )",
+                "store  (x addr -- )",
+                "fetch  (addr -- x)",
+                "square  (x -- y)",
+                // breakpoint
+                "square  (x -- y)",
+            ]
+        );
+        assert!(output.contains("   stack: [3:i32, 1:i32]\n"));
+        assert!(output.contains("   stack: [3:i32]\n"));
+        assert!(output.contains("   locals: x = 3\n"));
+        assert!(output
+            .contains("   in twice: 1 push_frame 0 store 0 fetch >square< square 1 pop_frame\n"));
+    }
+
+    #[test]
+    fn breakpoints() {
+        let state = &mut State::new();
+        stdlib(state).unwrap();
+        state.run("USE std:").unwrap();
+        state.run(": square ( x -- y ) dup * ;").unwrap();
+
+        let (result, output) = debug(state, "2 square square", Run::Continue, "c\nd square\nc\n");
+        result.unwrap();
+        state.assert_stack(&[16]);
+        assert_eq!(stops(&output), vec!["square  (x -- y)", "square  (x -- y)"]);
+
+        let (result, _) = debug(state, "2 square", Run::Continue, "q\n");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn trace_while_debugging() {
+        let state = &mut State::new();
+        stdlib(state).unwrap();
+        state.run("USE std:").unwrap();
+        state.run(": square ( x -- y ) dup * ;").unwrap();
+
+        let (trace, buffer) = Output::capture();
+        let tracer = state.tracer();
+        tracer.set_output(trace);
+        tracer.trace_word("::square");
+
+        let (result, output) = debug(state, "2 square", Run::Continue, "c\n");
+        result.unwrap();
+        assert_eq!(stops(&output), vec!["square  (x -- y)"]);
+        assert_eq!(buffer.contents(), "> square  [2]\n< square  [4]\n");
+    }
+}
@@ -0,0 +1,270 @@
+use forthen_core::objects::prelude::*;
+use forthen_core::State;
+use std::io::IsTerminal;
+
+const TAG: &str = "\x1b[2;36m";
+const NUMBER: &str = "\x1b[33m";
+const STRING: &str = "\x1b[32m";
+const CONSTANT: &str = "\x1b[35m";
+const ERROR: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// How the REPL shows the stack before each prompt
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackDisplay {
+    /// maximum number of characters per line
+    pub width: usize,
+    /// maximum number of items, counted from the top
+    pub depth: usize,
+    /// one item per line instead of all on one line
+    pub lines: bool,
+    pub types: bool,
+    pub colors: bool,
+}
+
+impl Default for StackDisplay {
+    fn default() -> Self {
+        StackDisplay {
+            width: 70,
+            depth: 20,
+            lines: false,
+            types: true,
+            colors: std::io::stdout().is_terminal(),
+        }
+    }
+}
+
+/// A stack item prepared for display
+struct Item {
+    tag: String,
+    repr: String,
+    color: &'static str,
+}
+
+impl StackDisplay {
+    /// Change a setting given as `name value`, e.g. `width 100` or `lines`.
+    pub fn configure(&mut self, setting: &str) -> Result<(), String> {
+        let words: Vec<_> = setting.split_whitespace().collect();
+        match words[..] {
+            ["width", n] => self.width = parse_number(n)?,
+            ["depth", n] => self.depth = parse_number(n)?,
+            ["lines"] => self.lines = true,
+            ["inline"] => self.lines = false,
+            ["types", on] => self.types = parse_switch(on)?,
+            ["colors", on] => self.colors = parse_switch(on)?,
+            _ => {
+                return Err(format!(
+                    "Unknown stack display setting {:?}. Expected width N, depth N, lines, \
+                     inline, types on|off or colors on|off.",
+                    setting
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    pub fn print(&self, state: &State) {
+        println!("{}", self.format(state));
+    }
+
+    pub fn format(&self, state: &State) -> String {
+        let items: Vec<_> = state
+            .stack
+            .iter()
+            .rev()
+            .take(self.depth)
+            .map(|obj| describe(obj, state))
+            .collect();
+        let hidden = state.stack.len() - items.len();
+
+        if self.lines {
+            self.format_lines(items, hidden)
+        } else {
+            self.format_inline(items, hidden)
+        }
+    }
+
+    fn format_lines(&self, items: Vec<Item>, hidden: usize) -> String {
+        let tag_width = items.iter().map(|item| item.tag.len()).max().unwrap_or(0);
+
+        let mut lines = vec![];
+        if hidden > 0 {
+            lines.push(format!("  .. {} more", hidden));
+        }
+        for (depth, item) in items.iter().enumerate().rev() {
+            let mut line = format!("{:>3}  ", depth);
+            if self.types {
+                line += &self.paint(TAG, &format!("{:1$}", item.tag, tag_width));
+                line += "  ";
+            }
+            let room = self.width.saturating_sub(5 + tag_width + 2);
+            line += &self.paint(item.color, &truncate(&item.repr, room));
+            lines.push(line);
+        }
+        lines.join("\n")
+    }
+
+    fn format_inline(&self, items: Vec<Item>, hidden: usize) -> String {
+        let mut parts = vec![];
+        let mut length = 2;
+        let mut hidden = hidden;
+
+        for item in &items {
+            let tag_length = if self.types { item.tag.len() + 1 } else { 0 };
+            let mut repr = item.repr.clone();
+            if parts.is_empty() {
+                // always show at least part of the top item
+                repr = truncate(&repr, self.width.saturating_sub(length + tag_length + 2));
+            }
+            let part_length = repr.chars().count() + tag_length + 2;
+            if length + part_length > self.width && !parts.is_empty() {
+                hidden += items.len() - parts.len();
+                break;
+            }
+            length += part_length;
+
+            let mut part = self.paint(item.color, &repr);
+            if self.types {
+                part += &self.paint(TAG, &format!(":{}", item.tag));
+            }
+            parts.push(part);
+        }
+
+        if hidden > 0 {
+            parts.push("..".to_string());
+        }
+        parts.reverse();
+        format!("[{}]", parts.join(", "))
+    }
+
+    fn paint(&self, color: &str, text: &str) -> String {
+        if self.colors && !color.is_empty() {
+            format!("{}{}{}", color, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+fn describe(obj: &Object, state: &State) -> Item {
+    let (tag, color) = match obj {
+        Object::None => ("none".to_string(), CONSTANT),
+        Object::False | Object::True => ("bool".to_string(), CONSTANT),
+        Object::I32(_) => ("i32".to_string(), NUMBER),
+        Object::Word(_) => ("word".to_string(), ""),
+        Object::Function(_) => ("func".to_string(), ""),
+        Object::List(list) => (format!("list[{}]", list.len()), ""),
+        Object::String(_) => ("str".to_string(), STRING),
+        Object::Table(_) => ("table".to_string(), ""),
+        Object::Extension(_) => ("object".to_string(), ""),
+    };
+
+    match safe_repr(obj, state) {
+        Ok(repr) => Item { tag, repr, color },
+        Err(msg) => Item {
+            tag,
+            repr: repr_failed(obj, &msg),
+            color: ERROR,
+        },
+    }
+}
+
+/// The representation of an object, or a note why it could not be computed
+pub fn repr(obj: &Object, state: &State) -> String {
+    safe_repr(obj, state).unwrap_or_else(|msg| repr_failed(obj, &msg))
+}
+
+fn repr_failed(obj: &Object, msg: &str) -> String {
+    format!("{} <repr failed: {}>", obj.repr_sys(), msg)
+}
+
+/// Call `repr` in a separate state, so that user defined `__repr__` methods cannot disturb the
+/// stack and their errors are caught.
+fn safe_repr(obj: &Object, state: &State) -> Result<String, String> {
+    let sub = &mut state.substate();
+    obj.repr(sub).map_err(|e| e.to_string())?;
+    match sub.stack.pop() {
+        Some(Object::String(s)) => Ok(s.to_string()),
+        Some(other) => Err(format!("returned {} instead of a string", other.repr_sys())),
+        None => Err("returned nothing".to_string()),
+    }
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        let mut short: String = text.chars().take(width.saturating_sub(2)).collect();
+        short += "..";
+        short
+    }
+}
+
+fn parse_number(n: &str) -> Result<usize, String> {
+    n.parse()
+        .map_err(|_| format!("Expected a number, got {:?}.", n))
+}
+
+fn parse_switch(on: &str) -> Result<bool, String> {
+    match on {
+        "on" => Ok(true),
+        "off" => Ok(false),
+        _ => Err(format!("Expected on or off, got {:?}.", on)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forthen_std::stdlib;
+
+    #[test]
+    fn stack_display() {
+        let mut state = State::new();
+        stdlib(&mut state).unwrap();
+        state.run("USE std:").unwrap();
+        state.run("None 1 \"two\" [ 3 4 5 ]").unwrap();
+
+        let mut display = StackDisplay {
+            colors: false,
+            ..StackDisplay::default()
+        };
+        assert_eq!(
+            display.format(&state),
+            "[None:none, 1:i32, \"two\":str, [3, 4, 5]:list[3]]"
+        );
+
+        display.configure("types off").unwrap();
+        display.configure("depth 2").unwrap();
+        assert_eq!(display.format(&state), "[.., \"two\", [3, 4, 5]]");
+
+        display.configure("width 15").unwrap();
+        assert_eq!(display.format(&state), "[.., [3, 4, 5]]");
+
+        display.configure("types on").unwrap();
+        display.configure("width 20").unwrap();
+        display.configure("lines").unwrap();
+        assert_eq!(
+            display.format(&state),
+            "  .. 2 more\n  1  str      \"two\"\n  0  list[3]  [3, ..",
+        );
+
+        assert!(display.configure("width wide").is_err());
+        assert!(display.configure("bogus").is_err());
+
+        // a failing __repr__ is shown, not propagated
+        state.stack.clear();
+        state
+            .run(": broken ( -- t ) {} {} [ \"broken\" error ] set_attr __repr__ set_metatable ;")
+            .unwrap();
+        state.run("broken").unwrap();
+        let text = StackDisplay {
+            width: 200,
+            colors: false,
+            ..StackDisplay::default()
+        }
+        .format(&state);
+        assert!(text.contains("<repr failed: Runtime Error: broken>:table"));
+        assert_eq!(state.stack.len(), 1);
+    }
+}
@@ -0,0 +1,288 @@
+use forthen_core::{Entry, ModuleRef, State};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Write one documentation page per module, starting at the root module.
+pub fn generate(state: &State, outdir: &Path, html: bool) -> io::Result<()> {
+    fs::create_dir_all(outdir)?;
+    let mut modules = vec![state.root_module().clone()];
+    while let Some(module) = modules.pop() {
+        let page = Page::new(&module);
+        let name = page_name(&module.path());
+        fs::write(outdir.join(format!("{}.md", name)), page.to_markdown())?;
+        if html {
+            fs::write(outdir.join(format!("{}.html", name)), page.to_html())?;
+        }
+        modules.extend(submodules(&module));
+    }
+    Ok(())
+}
+
+fn submodules(module: &ModuleRef) -> Vec<ModuleRef> {
+    let mut names = module.submodule_names();
+    names.sort();
+    names
+        .iter()
+        .filter_map(|name| module.access_path(name))
+        .collect()
+}
+
+/// Documentation of a single module
+struct Page {
+    path: String,
+    parent: Option<String>,
+    submodules: Vec<String>,
+    words: Vec<WordDoc>,
+}
+
+struct WordDoc {
+    name: String,
+    stack_effect: String,
+    kind: &'static str,
+    doc: Option<String>,
+    location: Option<String>,
+    /// Path of the defining module, if the word was imported from elsewhere
+    imported_from: Option<String>,
+}
+
+impl Page {
+    fn new(module: &ModuleRef) -> Self {
+        let path = module.path();
+
+        let mut names = module.local_keys();
+        names.sort();
+        let words = names
+            .iter()
+            .filter_map(|name| module.local_lookup(name))
+            .map(|entry| WordDoc::new(&entry, &path))
+            .collect();
+
+        Page {
+            parent: module.parent().map(|parent| parent.path()),
+            submodules: submodules(module).iter().map(ModuleRef::path).collect(),
+            words,
+            path,
+        }
+    }
+
+    fn imported_words(&self) -> impl Iterator<Item = (&WordDoc, &str)> {
+        self.words.iter().filter_map(|word| {
+            word.imported_from
+                .as_ref()
+                .map(|module| (word, module.as_str()))
+        })
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut md = format!("# Module `{}`\n\n", display_path(&self.path));
+
+        if let Some(ref parent) = self.parent {
+            md += &format!("Parent: {}\n\n", md_module_link(parent, None));
+        }
+
+        if !self.submodules.is_empty() {
+            md += "## Submodules\n\n";
+            for sub in &self.submodules {
+                md += &format!("- {}\n", md_module_link(sub, None));
+            }
+            md += "\n";
+        }
+
+        md += "## Words\n\n";
+        for word in self.words.iter().filter(|w| w.imported_from.is_none()) {
+            md += &format!(
+                "<a id=\"{}\"></a>\n### `{}`\n\n",
+                anchor(&word.name),
+                word.name
+            );
+            md += &format!("`{}` &mdash; {}\n\n", word.stack_effect, word.kind);
+            if let Some(ref doc) = word.doc {
+                md += &format!("{}\n\n", doc);
+            }
+            if let Some(ref location) = word.location {
+                md += &format!("Defined at `{}`\n\n", location);
+            }
+        }
+
+        let imported: Vec<_> = self.imported_words().collect();
+        if !imported.is_empty() {
+            md += "## Imported words\n\n";
+            for (word, module) in imported {
+                md += &format!(
+                    "- [`{}`]({}) from `{}`\n",
+                    word.name,
+                    link_target(module, Some(&word.name), "md"),
+                    display_path(module)
+                );
+            }
+            md += "\n";
+        }
+
+        md
+    }
+
+    fn to_html(&self) -> String {
+        let title = format!("Module {}", display_path(&self.path));
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n<h1>{}</h1>\n",
+            escape(&title),
+            escape(&title)
+        );
+
+        if let Some(ref parent) = self.parent {
+            html += &format!("<p>Parent: {}</p>\n", html_module_link(parent, None));
+        }
+
+        if !self.submodules.is_empty() {
+            html += "<h2>Submodules</h2>\n<ul>\n";
+            for sub in &self.submodules {
+                html += &format!("<li>{}</li>\n", html_module_link(sub, None));
+            }
+            html += "</ul>\n";
+        }
+
+        html += "<h2>Words</h2>\n";
+        for word in self.words.iter().filter(|w| w.imported_from.is_none()) {
+            html += &format!(
+                "<h3 id=\"{}\"><code>{}</code></h3>\n",
+                anchor(&word.name),
+                escape(&word.name)
+            );
+            html += &format!(
+                "<p><code>{}</code> &mdash; {}</p>\n",
+                escape(&word.stack_effect),
+                word.kind
+            );
+            if let Some(ref doc) = word.doc {
+                html += &format!("<p>{}</p>\n", escape(doc).replace('\n', "<br>\n"));
+            }
+            if let Some(ref location) = word.location {
+                html += &format!("<p>Defined at <code>{}</code></p>\n", escape(location));
+            }
+        }
+
+        let imported: Vec<_> = self.imported_words().collect();
+        if !imported.is_empty() {
+            html += "<h2>Imported words</h2>\n<ul>\n";
+            for (word, module) in imported {
+                html += &format!(
+                    "<li><a href=\"{}\"><code>{}</code></a> from <code>{}</code></li>\n",
+                    link_target(module, Some(&word.name), "html"),
+                    escape(&word.name),
+                    escape(&display_path(module))
+                );
+            }
+            html += "</ul>\n";
+        }
+
+        html += "</body>\n</html>\n";
+        html
+    }
+}
+
+impl WordDoc {
+    fn new(entry: &Entry, module: &str) -> Self {
+        let kind = match (entry.is_native(), entry.is_parsing()) {
+            (true, false) => "native",
+            (false, false) => "compound",
+            (true, true) => "native parsing word",
+            (false, true) => "parsing word",
+        };
+
+        WordDoc {
+            name: entry.name.to_string(),
+            stack_effect: format!("({})", entry.stack_effect),
+            kind,
+            doc: entry.doc.clone(),
+            location: entry.location.as_ref().map(ToString::to_string),
+            imported_from: if entry.module == module {
+                None
+            } else {
+                Some(entry.module.clone())
+            },
+        }
+    }
+}
+
+fn display_path(path: &str) -> String {
+    format!(":{}", path)
+}
+
+/// File name (without extension) of a module's page
+fn page_name(path: &str) -> String {
+    match path {
+        "" => "index".to_string(),
+        _ => path.replace(':', "."),
+    }
+}
+
+/// HTML id of a word; word names may contain any character but whitespace.
+fn anchor(name: &str) -> String {
+    let mut id = "word-".to_string();
+    for ch in name.chars() {
+        if ch.is_ascii_alphanumeric() || ch == '-' {
+            id.push(ch);
+        } else {
+            id += &format!("_{:x}_", ch as u32);
+        }
+    }
+    id
+}
+
+fn link_target(module: &str, word: Option<&str>, ext: &str) -> String {
+    match word {
+        None => format!("{}.{}", page_name(module), ext),
+        Some(word) => format!("{}.{}#{}", page_name(module), ext, anchor(word)),
+    }
+}
+
+fn md_module_link(module: &str, word: Option<&str>) -> String {
+    format!(
+        "[`{}`]({})",
+        display_path(module),
+        link_target(module, word, "md")
+    )
+}
+
+fn html_module_link(module: &str, word: Option<&str>) -> String {
+    format!(
+        "<a href=\"{}\"><code>{}</code></a>",
+        link_target(module, word, "html"),
+        escape(&display_path(module))
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forthen_std::stdlib;
+
+    #[test]
+    fn module_pages() {
+        let mut state = State::new();
+        stdlib(&mut state).unwrap();
+
+        let stack = state.lookup_module(":std:stack").unwrap();
+        let md = Page::new(&stack).to_markdown();
+        assert!(md.starts_with("# Module `:std:stack`\n\nParent: [`:std`](std.md)\n"));
+        assert!(md.contains(
+            "<a id=\"word-dup\"></a>\n### `dup`\n\n`(a -- a a)` &mdash; native\n\nDuplicate the top item.\n"
+        ));
+
+        let std = state.lookup_module(":std").unwrap();
+        let md = Page::new(&std).to_markdown();
+        assert!(md.contains("- [`:std:stack`](std.stack.md)\n"));
+        assert!(md.contains("- [`dup`](std.stack.md#word-dup) from `:std:stack`\n"));
+
+        let html = Page::new(&stack).to_html();
+        assert!(html.contains("<h3 id=\"word-_2e_s\"><code>.s</code></h3>"));
+    }
+}
@@ -0,0 +1,306 @@
+use forthen_core::parsing::{is_unterminated_string, tokenize_with_offsets};
+use forthen_core::{ModuleRef, State};
+use rustyline::completion::Completer;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::Helper;
+use std::borrow::Cow;
+
+const UNBALANCED: &str = "\x1b[1;31m";
+const HINT: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+/// Completion, hints and highlighting for the REPL.
+///
+/// Holds references to the modules of the state, so it must be recreated when the current module
+/// changes. `previous` holds earlier lines of incomplete input, which brackets on the current line
+/// are matched against.
+pub struct ReplHelper {
+    current_module: ModuleRef,
+    root_module: ModuleRef,
+    previous: String,
+}
+
+impl ReplHelper {
+    pub fn new(state: &State, previous: &str) -> Self {
+        ReplHelper {
+            current_module: state.current_module.clone(),
+            root_module: state.root_module().clone(),
+            previous: previous.to_string(),
+        }
+    }
+
+    /// Words and submodules (ending in `:`) that start with `prefix`.
+    ///
+    /// Prefixes containing a `:` are completed relative to the module they name.
+    fn candidates(&self, prefix: &str) -> Vec<String> {
+        let (module, path, partial) = match prefix.rfind(':') {
+            None => (Some(self.current_module.clone()), "", prefix),
+            Some(i) => {
+                let (path, partial) = (&prefix[..=i], &prefix[i + 1..]);
+                let module = match path.strip_prefix(':') {
+                    Some("") => Some(self.root_module.clone()),
+                    Some(abs) => self.root_module.access_path(abs.trim_end_matches(':')),
+                    None => self.current_module.access_path(path.trim_end_matches(':')),
+                };
+                (module, path, partial)
+            }
+        };
+
+        let module = match module {
+            Some(module) => module,
+            None => return vec![],
+        };
+
+        let words = if path.is_empty() {
+            module.keys()
+        } else {
+            module.local_keys()
+        };
+
+        let mut names: Vec<String> = module
+            .submodule_names()
+            .into_iter()
+            .map(|name| name + ":")
+            .chain(words.iter().map(|name| name.to_string()))
+            .filter(|name| name.starts_with(partial))
+            .map(|name| format!("{}{}", path, name))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    fn stack_effect(&self, name: &str) -> Option<String> {
+        let entry = self.current_module.lookup(name)?;
+        if entry.is_parsing() {
+            None
+        } else {
+            Some(format!("({})", entry.stack_effect))
+        }
+    }
+}
+
+/// Start of the token that ends at `pos`
+fn token_start(line: &str, pos: usize) -> usize {
+    line[..pos]
+        .rfind(|ch: char| ch.is_whitespace() || ch == '(' || ch == ')')
+        .map_or(0, |i| i + 1)
+}
+
+/// A bracket, definition or string that has not been closed yet
+struct Open<'a> {
+    token: &'a str,
+    span: (usize, usize),
+    /// name of the word being defined, for definitions
+    name: Option<&'a str>,
+}
+
+impl<'a> Open<'a> {
+    fn describe(&self) -> String {
+        match self.name {
+            Some(name) => format!("{} {}", self.token, name),
+            None => self.token.to_string(),
+        }
+    }
+}
+
+/// Matching of brackets and definitions in a piece of code
+///
+/// `[` pairs with `]`, `(` with `)`, and `:`, `::`, `SYNTAX:` and `LET:` with `;`.
+/// Inside `( ... )` comments only parentheses count.
+struct Scan<'a> {
+    open: Vec<Open<'a>>,
+    /// closing tokens without matching opener
+    stray: Vec<(usize, usize)>,
+}
+
+impl<'a> Scan<'a> {
+    fn new(code: &'a str) -> Self {
+        let mut open: Vec<Open> = vec![];
+        let mut stray = vec![];
+
+        for (offset, token) in tokenize_with_offsets(code) {
+            let span = (offset, offset + token.len());
+
+            if is_unterminated_string(token) {
+                open.push(Open {
+                    token: "\"",
+                    span,
+                    name: None,
+                });
+                continue;
+            }
+
+            let in_comment = matches!(open.last(), Some(Open { token: "(", .. }));
+            if in_comment && token != "(" && token != ")" {
+                continue;
+            }
+
+            if let Some(top) = open.last_mut() {
+                if is_definition(top.token) && top.name.is_none() {
+                    top.name = Some(token);
+                    continue;
+                }
+            }
+
+            let opener = match token {
+                "[" | "(" => None,
+                _ if is_definition(token) => None,
+                "]" => Some("["),
+                ")" => Some("("),
+                ";" => Some(":"),
+                _ => continue,
+            };
+
+            match (opener, open.last()) {
+                (None, _) => open.push(Open {
+                    token,
+                    span,
+                    name: None,
+                }),
+                (Some(opener), Some(top))
+                    if top.token == opener || (opener == ":" && is_definition(top.token)) =>
+                {
+                    open.pop();
+                }
+                (Some(_), _) => stray.push(span),
+            }
+        }
+
+        Scan { open, stray }
+    }
+
+    /// Byte ranges of everything that is not balanced
+    fn unbalanced(&self) -> Vec<(usize, usize)> {
+        let mut bad = self.stray.clone();
+        bad.extend(self.open.iter().map(|open| open.span));
+        bad.sort();
+        bad
+    }
+}
+
+/// Byte ranges of the brackets and definitions that are not closed or not opened.
+fn unbalanced(code: &str) -> Vec<(usize, usize)> {
+    Scan::new(code).unbalanced()
+}
+
+/// Description of the innermost construct that still needs to be closed, if the code is incomplete.
+pub fn pending(code: &str) -> Option<String> {
+    Scan::new(code).open.last().map(Open::describe)
+}
+
+fn is_definition(token: &str) -> bool {
+    matches!(token, ":" | "::" | "SYNTAX:" | "LET:")
+}
+
+impl Completer for ReplHelper {
+    type Candidate = String;
+
+    fn complete(&self, line: &str, pos: usize) -> rustyline::Result<(usize, Vec<String>)> {
+        let start = token_start(line, pos);
+        Ok((start, self.candidates(&line[start..pos])))
+    }
+}
+
+impl Hinter for ReplHelper {
+    fn hint(&self, line: &str, pos: usize) -> Option<String> {
+        if pos < line.len() || line.ends_with(char::is_whitespace) {
+            return None;
+        }
+        let name = &line[token_start(line, pos)..pos];
+        self.stack_effect(name)
+            .map(|effect| format!("  {}", effect))
+    }
+}
+
+impl Highlighter for ReplHelper {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        let offset = self.previous.len();
+        let bad: Vec<_> = unbalanced(&(self.previous.clone() + line))
+            .into_iter()
+            .filter(|&(start, _)| start >= offset)
+            .map(|(start, end)| (start - offset, end - offset))
+            .collect();
+        if bad.is_empty() {
+            return Cow::Borrowed(line);
+        }
+
+        let mut highlighted = String::new();
+        let mut last = 0;
+        for (start, end) in bad {
+            highlighted += &line[last..start];
+            highlighted += UNBALANCED;
+            highlighted += &line[start..end];
+            highlighted += RESET;
+            last = end;
+        }
+        highlighted += &line[last..];
+        Cow::Owned(highlighted)
+    }
+
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        Cow::Owned(format!("{}{}{}", HINT, hint, RESET))
+    }
+
+    fn highlight_char(&self, _line: &str, _pos: usize) -> bool {
+        true
+    }
+}
+
+impl Helper for ReplHelper {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forthen_std::stdlib;
+
+    #[test]
+    fn completion() {
+        let mut state = State::new();
+        stdlib(&mut state).unwrap();
+        state.run("USE std:").unwrap();
+        let helper = ReplHelper::new(&state, "");
+
+        let (start, candidates) = helper.complete("1 du", 4).unwrap();
+        assert_eq!(start, 2);
+        assert_eq!(candidates, vec!["dup"]);
+
+        let (_, candidates) = helper.complete("USE std:st", 10).unwrap();
+        assert_eq!(candidates, vec!["std:stack:", "std:store"]);
+        let (_, candidates) = helper.complete("USE :std:stack:ro", 17).unwrap();
+        assert_eq!(candidates, vec![":std:stack:rot"]);
+        assert!(helper.complete("nowhere:x", 9).unwrap().1.is_empty());
+
+        assert_eq!(helper.hint("1 dup", 5), Some("  (a -- a a)".to_string()));
+        assert_eq!(helper.hint("1 dup ", 6), None);
+        assert_eq!(helper.hint("USE", 3), None);
+    }
+
+    #[test]
+    fn brackets() {
+        assert!(unbalanced(": foo ( a -- b ) [ 1 ] call ;").is_empty());
+        assert!(unbalanced("\"[\" ( ] ; )").is_empty());
+        assert_eq!(unbalanced("[ 1 ] ]"), vec![(6, 7)]);
+        assert_eq!(unbalanced(": foo [ ;"), vec![(0, 1), (6, 7), (8, 9)]);
+        assert_eq!(unbalanced("SYNTAX: x ( a"), vec![(0, 7), (10, 11)]);
+        assert_eq!(unbalanced("( [ ) \"a ]"), vec![(6, 10)]);
+    }
+
+    #[test]
+    fn incomplete_input() {
+        assert_eq!(pending("1 2 +"), None);
+        assert_eq!(pending("1 ]"), None);
+        assert_eq!(pending(": foo ( x -- y )\n"), Some(": foo".to_string()));
+        assert_eq!(pending(":: foo ( x -- y\n"), Some("(".to_string()));
+        assert_eq!(pending(": foo [ 1\n"), Some("[".to_string()));
+        assert_eq!(pending(": foo \"a ; b\n"), Some("\"".to_string()));
+        assert_eq!(pending(": foo \"a ; b\" ;"), None);
+        assert_eq!(pending("SYNTAX:"), Some("SYNTAX:".to_string()));
+
+        let state = State::new();
+        let helper = ReplHelper::new(&state, ": foo [ 1\n");
+        assert_eq!(helper.highlight("] ;", 0), "] ;");
+        assert_eq!(helper.highlight("] ; ]", 0), "] ; \x1b[1;31m]\x1b[0m");
+    }
+}
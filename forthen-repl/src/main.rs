@@ -1,116 +1,488 @@
+mod cli;
+mod debugger;
+mod display;
+mod doc;
+mod helper;
+mod test_runner;
+mod undo;
+
+use cli::{Options, Script};
+use debugger::{Debugger, Run};
+use display::StackDisplay;
 use error_chain::ChainedError;
 use forthen_core::errors::*;
-use forthen_core::objects::prelude::*;
-use forthen_core::State;
+use forthen_core::{CallHook, Coverage, Input, Object, Output, Profiler, Source, State};
 use forthen_std::*;
+use helper::{pending, ReplHelper};
+use rustyline::error::ReadlineError;
 use rustyline::Editor;
+use std::collections::HashMap;
 use std::env;
 use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use std::process;
+use std::rc::Rc;
+use std::time::Instant;
+use undo::UndoHistory;
 
 fn main() {
-    let mut state = State::new();
-    stdlib(&mut state).unwrap();
-
-    /*state.add_native_word("std:tier0", "( -- )", |state| tier0(state));
-    state.add_native_word("std:tier1", "( -- )", |state| tier1(state));
-    state.add_native_word("std:ops", "( -- )", |state| ops(state));
-    state.add_native_word("std:complex", "( -- )", |state| complex(state));*/
-    state.add_native_word("words", "( -- )", |state| {
-        state.print_dictionary();
-        Ok(())
-    });
-
-    let args: Vec<String> = env::args().collect();
+    let args: Vec<String> = env::args().skip(1).collect();
     let args: Vec<&str> = args.iter().map(String::as_str).collect();
 
-    let repl;
-    let file;
+    if let ["doc", doc_args @ ..] = &args[..] {
+        let mut state = new_state(&Options::default());
+        process::exit(run_doc(&mut state, doc_args));
+    }
+
+    if let ["test", test_args @ ..] = &args[..] {
+        process::exit(run_tests(test_args));
+    }
 
-    match &args[1..] {
-        ["-i", cmd] => {
-            repl = true;
-            file = Some(*cmd);
+    let options = match Options::parse(&args) {
+        Ok(options) => options,
+        Err(msg) => {
+            eprintln!("Invalid Arguments. {}\n\n{}", msg, cli::USAGE);
+            process::exit(2);
         }
-        [cmd] => {
-            repl = false;
-            file = Some(*cmd);
+    };
+
+    if options.help {
+        println!("{}", cli::USAGE);
+        return;
+    }
+
+    let mut state = new_state(&options);
+
+    let coverage = options.coverage.as_ref().map(|filename| {
+        let coverage = Coverage::new();
+        state.set_coverage(Some(coverage.clone()));
+        (filename, coverage)
+    });
+
+    let mut result = if options.profiling() {
+        profile_scripts(&mut state, &options)
+    } else {
+        run_scripts(&mut state, &options)
+    };
+
+    if let Some((filename, coverage)) = coverage {
+        state.set_coverage(None);
+        if let Err(e) = fs::write(filename, coverage.lcov()) {
+            result = result.and(Err(e.into()));
         }
-        [] => {
-            repl = true;
-            file = None;
+    }
+
+    match result {
+        Ok(()) => {}
+        Err(Error(ErrorKind::Exit(code), _)) => process::exit(code),
+        Err(e) => {
+            report_error(e);
+            if !options.interactive {
+                process::exit(1);
+            }
         }
-        _ => {
-            eprintln!("Invalid Arguments. Expected script, -i script, or nothing.");
-            return;
+    }
+
+    if options.repl() {
+        repl(&mut state, &options);
+    }
+}
+
+fn new_state(options: &Options) -> State {
+    let mut state = State::new();
+    state.set_input(Input::stdin());
+    for dir in &options.include_paths {
+        state.add_include_path(dir);
+    }
+    state.set_args(options.args.clone());
+
+    if !options.no_std {
+        stdlib(&mut state).unwrap();
+        io(&mut state).unwrap();
+    }
+
+    state.add_native_word(
+        "words",
+        "( -- )",
+        Some("Print all words visible from the current module."),
+        |state| state.print_dictionary(),
+    );
+
+    if let Err(e) = start_tracing(&mut state, options) {
+        report_error(e);
+        process::exit(1);
+    }
+
+    state
+}
+
+/// Set up tracing as requested with `--trace` and `--trace-file`.
+fn start_tracing(state: &mut State, options: &Options) -> Result<()> {
+    if let Some(ref filename) = options.trace_file {
+        let file = fs::File::create(filename)
+            .map_err(|e| ErrorKind::IoError(filename.clone(), e.to_string()))?;
+        state
+            .tracer()
+            .set_output(Output::new(io::LineWriter::new(file)));
+    }
+    if options.trace {
+        state.tracer().trace_all();
+    }
+    Ok(())
+}
+
+fn run_scripts(state: &mut State, options: &Options) -> Result<()> {
+    for script in &options.scripts {
+        run_script(state, script)?;
+    }
+    Ok(())
+}
+
+/// Run the scripts with a profiler, and report the results even if a script fails.
+fn profile_scripts(state: &mut State, options: &Options) -> Result<()> {
+    let profiler = Profiler::new();
+    let result = profiler.run(state, |state| run_scripts(state, options));
+
+    if options.profile {
+        eprint!("{}", profiler.report());
+    }
+    if let Some(ref filename) = options.profile_folded {
+        fs::write(filename, profiler.folded())?;
+    }
+    result
+}
+
+fn run_script(state: &mut State, script: &Script) -> Result<()> {
+    let source = match script {
+        Script::File(filename) => Source::new(filename.as_str(), read_file(filename)?),
+        Script::Stdin => {
+            let mut code = String::new();
+            io::stdin().read_to_string(&mut code)?;
+            Source::new("<stdin>", code)
         }
+        Script::Code(code) => Source::new("<-e>", code.as_str()),
+    };
+    state.run_source(source)
+}
+
+fn read_file(filename: &str) -> Result<String> {
+    fs::read_to_string(filename).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => ErrorKind::FileNotFound(filename.to_string()).into(),
+        _ => e.into(),
+    })
+}
+
+fn repl(state: &mut State, options: &Options) {
+    let mut rl = Editor::<ReplHelper>::new();
+    let history = dirs::home_dir().map(|home| home.join(".forthen_history"));
+    if let Some(ref history) = history {
+        // there is no history yet on first use
+        rl.load_history(history).ok();
     }
 
-    if let Some(filename) = file {
-        let code =
-            fs::read_to_string(filename).unwrap_or_else(|_| panic!("Unable to load {}", filename));
+    let mut session = Session::new(
+        options
+            .scripts
+            .iter()
+            .filter_map(|script| match script {
+                Script::File(filename) => Some(filename.clone()),
+                _ => None,
+            })
+            .collect(),
+    );
+
+    // lines of a construct that is not complete yet
+    let mut buffer = String::new();
+
+    loop {
+        let prompt = match pending(&buffer) {
+            Some(construct) => format!("{} .. ", construct),
+            None => {
+                println!();
+                session.display.print(state);
+                format!("{:?} >> ", state.current_mode())
+            }
+        };
+
+        rl.set_helper(Some(ReplHelper::new(state, &buffer)));
+
+        let line = match rl.readline(&prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) if !buffer.is_empty() => {
+                buffer.clear();
+                continue;
+            }
+            Err(ReadlineError::Eof) | Err(ReadlineError::Interrupted) => break,
+            Err(_) => {
+                println!("Input Error");
+                break;
+            }
+        };
+
+        // without a terminal, rustyline passes the line terminator on
+        buffer += line.trim_end_matches(['\n', '\r']);
+        buffer += "\n";
+        if pending(&buffer).is_some() {
+            continue;
+        }
+
+        let code = std::mem::take(&mut buffer);
+        rl.add_history_entry(code.trim_end());
+        if let Some(ref history) = history {
+            if let Err(e) = rl.save_history(history) {
+                eprintln!("Unable to save history to {}: {}", history.display(), e);
+            }
+        }
+
+        let result = match meta_command(&code) {
+            Some((":quit", _)) => break,
+            Some((cmd, arg)) => session.run_command(state, options, cmd, arg),
+            None => session.run_line(state, code),
+        };
 
-        match state.run(&code) {
+        match result {
             Ok(()) => {}
+            Err(Error(ErrorKind::Exit(code), _)) => process::exit(code),
             Err(e) => report_error(e),
         }
     }
+}
 
-    if !repl {
-        return;
+/// Split a REPL meta-command like `:load file` into command and argument.
+fn meta_command(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    let (cmd, arg) = match line.find(char::is_whitespace) {
+        Some(i) => (&line[..i], line[i..].trim()),
+        None => (line, ""),
+    };
+    match cmd {
+        ":load" | ":reload" | ":reset" | ":clear" | ":save-session" | ":time" | ":stack"
+        | ":undo" | ":redo" | ":snap" | ":restore" | ":debug" | ":break" | ":unbreak" | ":quit" => {
+            Some((cmd, arg))
+        }
+        _ => None,
     }
+}
 
-    let mut rl = Editor::<()>::new();
+/// What the REPL remembers about the current session
+struct Session {
+    /// files loaded with `:load` (or on the command line), for `:reload`
+    loaded: Vec<String>,
+    /// successfully evaluated input, for `:save-session`
+    transcript: Vec<String>,
+    display: StackDisplay,
+    /// data stack before each evaluated line, for `:undo` and `:redo`
+    history: UndoHistory,
+    /// stacks saved with `:snap`
+    snapshots: HashMap<String, Vec<Object>>,
+    /// installed while running code with `:debug` or while there are breakpoints
+    debugger: Rc<Debugger>,
+}
 
-    loop {
-        println!();
-        print_stack(&mut state, 70);
+impl Session {
+    fn new(loaded: Vec<String>) -> Self {
+        Session {
+            loaded,
+            transcript: vec![],
+            display: StackDisplay::default(),
+            history: UndoHistory::default(),
+            snapshots: HashMap::new(),
+            debugger: Rc::new(Debugger::new(Input::stdin(), Output::stdout())),
+        }
+    }
 
-        let prompt = &format!("{:?} >> ", state.current_mode());
+    fn run_line(&mut self, state: &mut State, code: String) -> Result<()> {
+        self.history.record(&state.stack);
+        if self.debugger.breakpoints().is_empty() {
+            state.run(&code)?;
+        } else {
+            self.debug(state, &code, Run::Continue)?;
+        }
+        self.transcript.push(code);
+        Ok(())
+    }
+
+    /// Run code with the debugger installed.
+    fn debug(&self, state: &mut State, code: &str, run: Run) -> Result<()> {
+        self.debugger.set_run(run);
+        self.debugger.set_display(self.display.clone());
+        let hook: Rc<dyn CallHook> = self.debugger.clone();
+        state.add_hook(hook.clone());
+        let result = state.run(code);
+        state.remove_hook(&hook);
+        result
+    }
 
-        match rl.readline(prompt) {
-            Ok(line) => {
-                rl.add_history_entry(line.as_str());
-                match state.run(&line) {
-                    Ok(()) => {}
-                    Err(e) => report_error(e),
+    fn run_command(
+        &mut self,
+        state: &mut State,
+        options: &Options,
+        cmd: &str,
+        arg: &str,
+    ) -> Result<()> {
+        if let ":load" | ":reload" | ":reset" | ":clear" | ":time" | ":debug" = cmd {
+            self.history.record(&state.stack);
+        }
+
+        match cmd {
+            ":load" => {
+                self.load(state, arg)?;
+                self.loaded.push(arg.to_string());
+            }
+            ":reload" => {
+                for filename in self.loaded.clone() {
+                    self.load(state, &filename)?;
                 }
             }
-            _ => {
-                println!("Input Error");
-                break;
+            ":reset" => {
+                *state = new_state(options);
+                self.transcript.clear();
+            }
+            ":clear" => state.stack.clear(),
+            ":save-session" => {
+                let mut code = self.transcript.join("");
+                if !code.is_empty() && !code.ends_with('\n') {
+                    code.push('\n');
+                }
+                fs::write(arg, code)?;
+                println!("Saved {} entries to {}", self.transcript.len(), arg);
+            }
+            ":time" => {
+                let start = Instant::now();
+                state.run(arg)?;
+                println!("{:?}", start.elapsed());
+                self.transcript.push(format!("{}\n", arg));
+            }
+            ":stack" if arg.is_empty() => println!("{:?}", self.display),
+            ":stack" => self
+                .display
+                .configure(arg)
+                .map_err(ErrorKind::RuntimeError)?,
+            ":undo" => {
+                if !self.history.undo(&mut state.stack) {
+                    return Err(ErrorKind::RuntimeError("Nothing to undo".to_string()).into());
+                }
+            }
+            ":redo" => {
+                if !self.history.redo(&mut state.stack) {
+                    return Err(ErrorKind::RuntimeError("Nothing to redo".to_string()).into());
+                }
+            }
+            ":snap" if arg.is_empty() => {
+                let mut names: Vec<_> = self.snapshots.keys().collect();
+                names.sort();
+                for name in names {
+                    println!("{} ({} items)", name, self.snapshots[name].len());
+                }
+            }
+            ":snap" => {
+                self.snapshots.insert(arg.to_string(), state.stack.clone());
+            }
+            ":restore" => match self.snapshots.get(arg) {
+                Some(stack) => {
+                    self.history.record(&state.stack);
+                    state.stack = stack.clone();
+                }
+                None => {
+                    return Err(
+                        ErrorKind::RuntimeError(format!("No snapshot named {:?}", arg)).into(),
+                    )
+                }
+            },
+            ":debug" => {
+                self.debug(state, arg, Run::Step)?;
+                self.transcript.push(format!("{}\n", arg));
+            }
+            ":break" if arg.is_empty() => {
+                println!("Breakpoints: {}", self.debugger.breakpoints().join(" "))
+            }
+            ":break" => self.debugger.add_breakpoint(arg),
+            ":unbreak" => {
+                if !self.debugger.remove_breakpoint(arg) {
+                    return Err(
+                        ErrorKind::RuntimeError(format!("No breakpoint on {:?}", arg)).into(),
+                    );
+                }
             }
+            _ => unreachable!("unknown meta command {}", cmd),
         }
+        Ok(())
+    }
+
+    fn load(&mut self, state: &mut State, filename: &str) -> Result<()> {
+        run_script(state, &Script::File(filename.to_string()))?;
+        self.transcript.push(format!("INCLUDE {}\n", filename));
+        Ok(())
     }
 }
 
-fn print_stack(state: &mut State, max_len: usize) {
-    let mut total_length = 0;
-    let mut top = vec![];
+/// `forthen doc [--html] [-o outdir] [script ...]`
+///
+/// Load the scripts and write documentation of all modules to outdir.
+fn run_doc(state: &mut State, args: &[&str]) -> i32 {
+    let mut html = false;
+    let mut outdir = "doc";
+    let mut scripts = vec![];
 
-    let stack_copy: Vec<_> = state
-        .stack
-        .iter()
-        .rev()
-        .cloned()
-        .take(max_len / 3)
-        .collect();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match *arg {
+            "--html" => html = true,
+            "-o" => match args.next() {
+                Some(dir) => outdir = dir,
+                None => {
+                    eprintln!("Invalid Arguments. Expected output directory after -o.");
+                    return 2;
+                }
+            },
+            script => scripts.push(script),
+        }
+    }
 
-    for x in stack_copy {
-        x.repr(state).unwrap();
-        let repr = state.pop().unwrap().as_str().unwrap().to_string();
-        total_length += repr.len() + 2;
-        if total_length > max_len {
-            break;
+    for filename in scripts {
+        if let Err(e) = run_script(state, &Script::File(filename.to_string())) {
+            report_error(e);
+            return 1;
         }
-        top.push(repr);
     }
 
-    top.reverse();
+    if let Err(e) = doc::generate(state, Path::new(outdir), html) {
+        eprintln!("Unable to write documentation to {}: {}", outdir, e);
+        return 1;
+    }
 
-    if top.len() < state.stack.len() {
-        println!("[.., {}]", top.join(", "));
+    0
+}
+
+/// Run the tests found in the given files and directories, or in the current directory.
+fn run_tests(args: &[&str]) -> i32 {
+    let paths = if args.is_empty() { &["."][..] } else { args };
+    let files = match test_runner::discover(paths) {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("Unable to find tests: {}", e);
+            return 2;
+        }
+    };
+
+    let mut total = test_runner::Summary::default();
+    for file in files {
+        let mut state = new_state(&Options::default());
+        match test_runner::run_file(&mut state, &file, &mut io::stdout()) {
+            Ok(summary) => total.add(summary),
+            Err(e) => {
+                eprintln!("{}", e);
+                return 2;
+            }
+        }
+    }
+
+    println!("{} passed, {} failed", total.passed, total.failed);
+    if total.failed > 0 {
+        1
     } else {
-        println!("[{}]", top.join(", "));
+        0
     }
 }
 
@@ -118,3 +490,94 @@ fn report_error(e: Error) {
     eprintln!("{}", e);
     eprintln!("{}", e.display_chain().to_string());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forthen_core::objects::prelude::*;
+
+    #[test]
+    fn meta_commands() {
+        assert_eq!(meta_command(":load foo.fn\n"), Some((":load", "foo.fn")));
+        assert_eq!(meta_command(":time 1 2 +"), Some((":time", "1 2 +")));
+        assert_eq!(meta_command(":quit"), Some((":quit", "")));
+        assert_eq!(meta_command(": foo ( -- ) ;"), None);
+        assert_eq!(meta_command(":stack depth 3"), Some((":stack", "depth 3")));
+        assert_eq!(meta_command(":unknown"), None);
+
+        let dir = env::temp_dir().join(format!("forthen-session-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("lib.fn");
+        let saved = dir.join("session.fn");
+        fs::write(&script, ": answer ( -- x ) 42 ;").unwrap();
+
+        let options = Options::default();
+        let state = &mut new_state(&options);
+        let mut session = Session::new(vec![]);
+        let mut command = |state: &mut State, cmd, arg: &std::path::Path| {
+            session.run_command(state, &options, cmd, arg.to_str().unwrap())
+        };
+
+        command(state, ":load", &script).unwrap();
+        state.assert_run_pop("answer", &[42]);
+        state.run("1 2").unwrap();
+        command(state, ":clear", Path::new("")).unwrap();
+        state.assert_stack::<i32>(&[]);
+        command(state, ":save-session", &saved).unwrap();
+        assert_eq!(
+            fs::read_to_string(&saved).unwrap(),
+            format!("INCLUDE {}\n", script.display())
+        );
+
+        command(state, ":stack", Path::new("lines")).unwrap();
+        assert!(command(state, ":stack", Path::new("sideways")).is_err());
+
+        command(state, ":reset", Path::new("")).unwrap();
+        assert!(state.run("answer").is_err());
+        command(state, ":reload", Path::new("")).unwrap();
+        state.assert_run_pop("answer", &[42]);
+
+        assert!(session.display.lines);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn undo_and_snapshots() {
+        let options = Options::default();
+        let state = &mut new_state(&options);
+        let mut session = Session::new(vec![]);
+        state.run("USE std:").unwrap();
+
+        session.run_line(state, "1 2".to_string()).unwrap();
+        session.run_line(state, "[ 3 ]".to_string()).unwrap();
+        session
+            .run_command(state, &options, ":snap", "three")
+            .unwrap();
+        session.run_line(state, "4 push-back".to_string()).unwrap();
+        assert!(session
+            .run_line(state, "drop drop drop drop".to_string())
+            .is_err());
+        state.assert_stack::<i32>(&[]);
+
+        // the list was changed after the snapshot, but the snapshot still holds the old one
+        session.run_command(state, &options, ":undo", "").unwrap();
+        assert_eq!(state.stack[2].as_slice().unwrap(), &[3, 4]);
+        session
+            .run_command(state, &options, ":restore", "three")
+            .unwrap();
+        assert_eq!(state.stack[2].as_slice().unwrap(), &[3]);
+        assert!(session
+            .run_command(state, &options, ":restore", "four")
+            .is_err());
+
+        for _ in 0..3 {
+            session.run_command(state, &options, ":undo", "").unwrap();
+        }
+        state.assert_stack(&[1, 2]);
+        for _ in 0..3 {
+            session.run_command(state, &options, ":redo", "").unwrap();
+        }
+        assert!(session.run_command(state, &options, ":redo", "").is_err());
+        assert_eq!(state.stack[2].as_slice().unwrap(), &[3]);
+    }
+}
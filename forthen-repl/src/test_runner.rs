@@ -0,0 +1,159 @@
+use forthen_core::errors::*;
+use forthen_core::objects::prelude::*;
+use forthen_core::{Object, Output, Source, State};
+use forthen_std::collect_tests;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Files whose names end like this contain tests
+const TEST_SUFFIX: &str = "_test.fn";
+
+/// Number of passed and failed tests
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Summary {
+    pub passed: usize,
+    pub failed: usize,
+}
+
+impl Summary {
+    pub fn add(&mut self, other: Summary) {
+        self.passed += other.passed;
+        self.failed += other.failed;
+    }
+}
+
+/// Find test files in the given files and directories. Directories are searched recursively.
+pub fn discover(paths: &[&str]) -> io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for path in paths {
+        let path = Path::new(path);
+        if path.is_dir() {
+            find_in(path, &mut files)?;
+        } else {
+            files.push(path.to_path_buf());
+        }
+    }
+    Ok(files)
+}
+
+fn find_in(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries: Vec<_> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<_>>()?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            find_in(&path, files)?;
+        } else if path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().ends_with(TEST_SUFFIX))
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Load a test file into the state and run each test it defines in a fresh substate.
+///
+/// Failures are reported with the location of the test and whatever the test printed. A file
+/// that fails to load counts as a single failed test.
+pub fn run_file(state: &mut State, path: &Path, out: &mut dyn Write) -> io::Result<Summary> {
+    writeln!(out, "{}", path.display())?;
+    let mut summary = Summary::default();
+
+    if let Err(e) = load(state, path) {
+        writeln!(out, "  FAIL  <load>\n        {}", e)?;
+        summary.failed += 1;
+        return Ok(summary);
+    }
+
+    for test in collect_tests(state) {
+        let sub = &mut state.substate();
+        let (output, buffer) = Output::capture();
+        sub.set_output(output);
+
+        match Object::Word(test.clone()).call(sub) {
+            Ok(()) => {
+                writeln!(out, "  ok    {}", test.name)?;
+                summary.passed += 1;
+            }
+            Err(e) => {
+                match test.location {
+                    Some(ref location) => writeln!(out, "  FAIL  {}  ({})", test.name, location)?,
+                    None => writeln!(out, "  FAIL  {}", test.name)?,
+                }
+                writeln!(out, "        {}", e)?;
+                for line in buffer.contents().lines() {
+                    writeln!(out, "        | {}", line)?;
+                }
+                summary.failed += 1;
+            }
+        }
+    }
+    Ok(summary)
+}
+
+fn load(state: &mut State, path: &Path) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        state.add_include_path(dir);
+    }
+    let code = fs::read_to_string(path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => ErrorKind::FileNotFound(path.display().to_string()).into(),
+        _ => Error::from(e),
+    })?;
+    state.run_source(Source::new(path.to_string_lossy(), code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forthen_std::stdlib;
+
+    #[test]
+    fn run_test_files() {
+        let dir = std::env::temp_dir().join(format!("forthen-tests-{}", std::process::id()));
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("lib.fn"), ": square ( x -- y ) dup * ;").unwrap();
+        fs::write(
+            dir.join("sub").join("square_test.fn"),
+            "USE std:\nINCLUDE lib.fn\n\
+             TEST: squares 3 square 9 assert= ;\n\
+             TEST: prints \"hello\" . 2 square 5 assert= ;\n",
+        )
+        .unwrap();
+        fs::write(dir.join("broken_test.fn"), "USE std:\nTEST: x").unwrap();
+
+        let files = discover(&[dir.to_str().unwrap()]).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                dir.join("broken_test.fn"),
+                dir.join("sub").join("square_test.fn")
+            ]
+        );
+
+        let mut out = vec![];
+        let mut total = Summary::default();
+        for file in &files {
+            let state = &mut State::new();
+            stdlib(state).unwrap();
+            state.add_include_path(&dir);
+            total.add(run_file(state, file, &mut out).unwrap());
+        }
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            total,
+            Summary {
+                passed: 1,
+                failed: 2
+            }
+        );
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("  FAIL  <load>\n"));
+        assert!(out.contains("  ok    squares\n"));
+        assert!(out.contains("square_test.fn:4)\n        Assertion Failed: expected 5 but got 4\n        | \"hello\"\n"));
+    }
+}
@@ -0,0 +1,77 @@
+use forthen_core::Object;
+use std::collections::VecDeque;
+
+/// Number of stack states `:undo` can go back
+const UNDO_LIMIT: usize = 100;
+
+/// Undo and redo history of the data stack
+///
+/// Objects are reference counted and copied on write, so keeping a snapshot only clones the
+/// references.
+#[derive(Default)]
+pub struct UndoHistory {
+    undo: VecDeque<Vec<Object>>,
+    redo: Vec<Vec<Object>>,
+}
+
+impl UndoHistory {
+    /// Remember the stack before it is changed. This discards everything that could be redone.
+    pub fn record(&mut self, stack: &[Object]) {
+        if self.undo.len() == UNDO_LIMIT {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(stack.to_vec());
+        self.redo.clear();
+    }
+
+    /// Go back to the previously recorded stack, if there is one.
+    pub fn undo(&mut self, stack: &mut Vec<Object>) -> bool {
+        match self.undo.pop_back() {
+            Some(previous) => {
+                self.redo.push(std::mem::replace(stack, previous));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Revert the last `undo`, if no new changes have been recorded since.
+    pub fn redo(&mut self, stack: &mut Vec<Object>) -> bool {
+        match self.redo.pop() {
+            Some(next) => {
+                self.undo.push_back(std::mem::replace(stack, next));
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undo_redo() {
+        let mut history = UndoHistory::default();
+        let mut stack: Vec<Object> = vec![];
+        assert!(!history.undo(&mut stack));
+
+        for i in 0..UNDO_LIMIT as i32 + 5 {
+            history.record(&stack);
+            stack.push(Object::I32(i));
+        }
+
+        assert!(history.undo(&mut stack));
+        assert!(history.undo(&mut stack));
+        assert_eq!(stack.len(), UNDO_LIMIT + 3);
+        assert!(history.redo(&mut stack));
+        assert_eq!(stack.len(), UNDO_LIMIT + 4);
+
+        while history.undo(&mut stack) {}
+        assert_eq!(stack.len(), 5);
+
+        history.record(&stack);
+        assert!(!history.redo(&mut stack));
+    }
+}
@@ -9,6 +9,7 @@ pub fn branch(state: &mut State) -> Result<()> {
     state.add_native_word(
         "if",
         "(..a ? true(..a -- ..b) false(..a -- ..b) -- ..b)",
+        Some("Call the true branch if the condition is true, or the false branch otherwise."),
         |state| {
             let else_branch = state.pop()?;
             let if_branch = state.pop()?;
@@ -21,27 +22,38 @@ pub fn branch(state: &mut State) -> Result<()> {
         },
     );
 
-    state.add_native_word("cond", "(..a list -- ..b)", |state| {
-        let list = state.pop()?;
-
-        for row in list.as_slice()? {
-            match row.as_slice() {
-                Ok([cond, action]) => {
-                    cond.call(state)?;
-                    if state.pop_bool()? {
-                        return action.call(state);
+    state.add_native_word(
+        "cond",
+        "(..a list -- ..b)",
+        Some(
+            "Take a list of [ condition action ] pairs and call the action of the first condition \
+             that evaluates to true.",
+        ),
+        |state| {
+            let list = state.pop()?;
+            state.cover(&list);
+
+            for row in list.as_slice()? {
+                match row.as_slice() {
+                    Ok([cond, action]) => {
+                        // rows are not called, but count as executed when their condition is
+                        state.cover(row);
+                        cond.call(state)?;
+                        if state.pop_bool()? {
+                            return action.call(state);
+                        }
+                    }
+                    _ => {
+                        return Err(ErrorKind::TypeError(
+                            "Expected list of conditions/actions".to_string(),
+                        )
+                        .into());
                     }
-                }
-                _ => {
-                    return Err(ErrorKind::TypeError(
-                        "Expected list of conditions/actions".to_string(),
-                    )
-                    .into());
                 }
             }
-        }
-        Err(ErrorKind::TypeError("None of the conditions evaluated to true".to_string()).into())
-    });
+            Err(ErrorKind::TypeError("None of the conditions evaluated to true".to_string()).into())
+        },
+    );
 
     state.exit_mod().unwrap();
 
@@ -66,8 +78,8 @@ mod tests {
         state.run("USE branch:").unwrap();
         state.run("USE scope:").unwrap();
 
-        state.add_native_word("true", "( -- b)", |state| state.push(Object::True));
-        state.add_native_word("false", "( -- b)", |state| state.push(Object::False));
+        state.add_native_word("true", "( -- b)", None, |state| state.push(Object::True));
+        state.add_native_word("false", "( -- b)", None, |state| state.push(Object::False));
 
         state
             .run(":: dup   (x -- x x)   set x get x get x ;")
@@ -120,8 +132,8 @@ mod tests {
         state.run("USE stack:").unwrap();
         state.run("USE ops:").unwrap();
 
-        state.add_native_word("true", "( -- b)", |state| state.push(Object::True));
-        state.add_native_word("false", "( -- b)", |state| state.push(Object::False));
+        state.add_native_word("true", "( -- b)", None, |state| state.push(Object::True));
+        state.add_native_word("false", "( -- b)", None, |state| state.push(Object::False));
 
         state
             .run(
@@ -0,0 +1,421 @@
+use forthen_core::errors::*;
+use forthen_core::objects::callable::Callable;
+use forthen_core::objects::prelude::*;
+use forthen_core::{Mode, Object, State, WordId};
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Random inputs tried per check
+const CASES: usize = 100;
+
+/// Limit on the number of times a failing input is shrunk
+const SHRINK_STEPS: usize = 1000;
+
+/// Range of the integers generated for inputs without a generator
+const DEFAULT_RANGE: (i32, i32) = (-100, 100);
+
+/// Environment variable that fixes the random seed of every check, to reproduce a failure
+const SEED_VAR: &str = "FORTHEN_SEED";
+
+/// Load property based testing words into the dictionary
+pub fn check(state: &mut State) -> Result<()> {
+    state.new_mod("check".to_string())?;
+
+    let rng = Rng::from_time();
+
+    let r = rng.clone();
+    state.add_native_word(
+        "random-int",
+        "(lo hi -- n)",
+        Some("Push a random integer from lo to hi, inclusive."),
+        move |state| {
+            let hi = state.pop_i32()?;
+            let lo = state.pop_i32()?;
+            if lo > hi {
+                return Err(
+                    ErrorKind::RuntimeError(format!("empty range {} to {}", lo, hi)).into(),
+                );
+            }
+            state.push(r.range(lo, hi))
+        },
+    );
+
+    let r = rng.clone();
+    state.add_native_word(
+        "random-list",
+        "(gen( -- x) n -- list)",
+        Some("Push a list of up to n items, each pushed by calling gen."),
+        move |state| {
+            let n = state.pop_i32()?;
+            let gen = state.pop()?;
+            let len = r.range(0, n.max(0));
+            let items = (0..len)
+                .map(|_| generate(state, &gen))
+                .collect::<Result<_>>()?;
+            state.push(Object::List(Rc::new(items)))
+        },
+    );
+
+    let r = rng.clone();
+    state.add_native_word(
+        "check-with",
+        "(word generators property -- )",
+        Some(
+            "Call the word with random inputs and fail unless it leaves as many items as its \
+             stack effect declares and the property holds. generators is a table of quotations \
+             ( -- x) by input name, or None to generate integers. The property ( inputs -- ? ) \
+             is called with the same inputs, or may be None. Failing inputs are shrunk before \
+             they are reported, together with the random seed. Set the environment variable \
+             FORTHEN_SEED to that seed to reproduce the failure.",
+        ),
+        move |state| {
+            let property = match state.pop()? {
+                Object::None => None,
+                quot => Some(quot),
+            };
+            let generators = state.pop()?;
+            let word = match state.pop()? {
+                Object::Word(word) => word,
+                obj => {
+                    return Err(
+                        ErrorKind::TypeError(format!("{} is not a word", obj.repr_sys())).into(),
+                    )
+                }
+            };
+            Check::new(word, generators, property)?.run(state, &r)
+        },
+    );
+
+    let r = rng;
+    state.add_native_parse_word(
+        "check",
+        Some(
+            "`check word` calls the word with random integers and fails unless it leaves as many \
+             items as its stack effect declares. See `check-with`.",
+        ),
+        move |state| {
+            let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+            let word = state
+                .lookup(&name)?
+                .ok_or_else(|| ErrorKind::UnknownWord(name.clone()))?;
+            let check = Check::new(word, Object::None, None)?;
+            let r = r.clone();
+            let func = Object::Function(Callable::new_const(move |state| check.run(state, &r)));
+            match state.current_mode() {
+                Mode::Eval => func.call(state),
+                Mode::Compile => state.compile_object(func),
+            }
+        },
+    );
+
+    state.exit_mod().unwrap();
+
+    Ok(())
+}
+
+/// A word together with how to test it
+struct Check {
+    word: WordId,
+    inputs: Vec<String>,
+    outputs: usize,
+    generators: Object,
+    property: Option<Object>,
+}
+
+impl Check {
+    fn new(word: WordId, generators: Object, property: Option<Object>) -> Result<Self> {
+        let (inputs, outputs) = word.stack_effect.fixed_arity().ok_or_else(|| {
+            ErrorKind::TypeError(format!(
+                "Can't check {} because its stack effect ({}) has no fixed number of items",
+                word.name, word.stack_effect
+            ))
+        })?;
+        Ok(Check {
+            word,
+            inputs,
+            outputs,
+            generators,
+            property,
+        })
+    }
+
+    /// Run the check with a fresh seed, or the one given in the environment.
+    fn run(&self, state: &State, rng: &Rng) -> Result<()> {
+        let seed = match std::env::var(SEED_VAR) {
+            Ok(seed) => seed.parse().map_err(|_| {
+                ErrorKind::RuntimeError(format!("{} is not a valid seed: {}", SEED_VAR, seed))
+            })?,
+            Err(_) => rng.next(),
+        };
+        self.run_seeded(state, rng, seed)
+    }
+
+    /// Run the check after seeding the random number generator that generators use too.
+    fn run_seeded(&self, state: &State, rng: &Rng, seed: u64) -> Result<()> {
+        rng.seed(seed);
+        for _ in 0..CASES {
+            let inputs = self.generate(state, rng)?;
+            if let Some(reason) = self.failure(state, &inputs)? {
+                let (inputs, reason) = self.shrink(state, inputs, reason)?;
+                return Err(ErrorKind::AssertionError(format!(
+                    "check {} ({}) failed for inputs {}: {} (seed {})",
+                    self.word.name,
+                    self.word.stack_effect,
+                    list_text(&inputs),
+                    reason,
+                    seed
+                ))
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    fn generate(&self, state: &State, rng: &Rng) -> Result<Vec<Object>> {
+        self.inputs
+            .iter()
+            .map(|name| match self.generators.get_attr(name) {
+                Some(gen) => generate(&mut state.substate(), &gen),
+                None => Ok(Object::I32(rng.range(DEFAULT_RANGE.0, DEFAULT_RANGE.1))),
+            })
+            .collect()
+    }
+
+    /// Why the inputs make the check fail, or `None` if it passes.
+    fn failure(&self, state: &State, inputs: &[Object]) -> Result<Option<String>> {
+        // detects words that consume more items than they declare
+        let below = Rc::new("<below>".to_string());
+
+        let sub = &mut state.substate();
+        sub.push(Object::String(below.clone()))?;
+        sub.stack.extend(inputs.iter().cloned());
+        if let Err(e) = Object::Word(self.word.clone()).call(sub) {
+            return Ok(Some(format!("raised {}", e)));
+        }
+        let untouched =
+            matches!(sub.stack.first(), Some(Object::String(s)) if Rc::ptr_eq(s, &below));
+        if !untouched || sub.stack.len() != 1 + self.outputs {
+            return Ok(Some(format!(
+                "expected {} outputs on top of the stack, but found {}",
+                self.outputs,
+                list_text(&sub.stack)
+            )));
+        }
+
+        if let Some(ref property) = self.property {
+            let sub = &mut state.substate();
+            sub.stack.extend(inputs.iter().cloned());
+            if let Err(e) = property.call(sub) {
+                return Ok(Some(format!("property raised {}", e)));
+            }
+            if !sub.pop_bool()? {
+                return Ok(Some("property does not hold".to_string()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Look for simpler inputs that still fail.
+    fn shrink(
+        &self,
+        state: &State,
+        mut inputs: Vec<Object>,
+        mut reason: String,
+    ) -> Result<(Vec<Object>, String)> {
+        let mut steps = 0;
+        'outer: while steps < SHRINK_STEPS {
+            for i in 0..inputs.len() {
+                for smaller in shrink(&inputs[i]) {
+                    let mut candidate = inputs.clone();
+                    candidate[i] = smaller;
+                    if let Some(r) = self.failure(state, &candidate)? {
+                        inputs = candidate;
+                        reason = r;
+                        steps += 1;
+                        continue 'outer;
+                    }
+                }
+            }
+            break;
+        }
+        Ok((inputs, reason))
+    }
+}
+
+/// Call a generator quotation and take the item it pushed.
+fn generate(state: &mut State, gen: &Object) -> Result<Object> {
+    gen.call(state)?;
+    state.pop()
+}
+
+/// Simpler variants of a value, simplest first
+fn shrink(obj: &Object) -> Vec<Object> {
+    match obj {
+        Object::I32(0) => vec![],
+        Object::I32(n) => {
+            let mut smaller = vec![0, n / 2, n - n.signum()];
+            smaller.dedup();
+            smaller.retain(|m| m != n);
+            smaller.into_iter().map(Object::I32).collect()
+        }
+        Object::String(s) if !s.is_empty() => {
+            let chars: Vec<_> = s.chars().collect();
+            let mut smaller = vec![String::new(), chars[..chars.len() / 2].iter().collect()];
+            smaller.push(chars[..chars.len() - 1].iter().collect());
+            smaller.dedup();
+            smaller
+                .into_iter()
+                .map(|s| Object::String(Rc::new(s)))
+                .collect()
+        }
+        Object::List(items) if !items.is_empty() => {
+            let mut smaller = vec![vec![], items[..items.len() / 2].to_vec()];
+            for i in 0..items.len() {
+                let mut fewer = items.to_vec();
+                fewer.remove(i);
+                smaller.push(fewer);
+            }
+            for (i, item) in items.iter().enumerate() {
+                for simpler in shrink(item) {
+                    let mut changed = items.to_vec();
+                    changed[i] = simpler;
+                    smaller.push(changed);
+                }
+            }
+            smaller
+                .into_iter()
+                .map(|items| Object::List(Rc::new(items)))
+                .collect()
+        }
+        _ => vec![],
+    }
+}
+
+fn list_text(items: &[Object]) -> String {
+    let items: Vec<_> = items.iter().map(|obj| obj.repr_sys()).collect();
+    format!("[{}]", items.join(", "))
+}
+
+/// Xorshift random number generator. Clones share their state.
+#[derive(Clone)]
+struct Rng(Rc<Cell<u64>>);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        let rng = Rng(Rc::new(Cell::new(0)));
+        rng.seed(seed);
+        rng
+    }
+
+    fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |t| t.as_nanos() as u64);
+        Rng::new(nanos)
+    }
+
+    fn seed(&self, seed: u64) {
+        // xorshift never leaves zero
+        self.0.set(seed.max(1));
+    }
+
+    fn next(&self) -> u64 {
+        let mut x = self.0.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0.set(x);
+        x
+    }
+
+    /// Random number from lo to hi, inclusive
+    fn range(&self, lo: i32, hi: i32) -> i32 {
+        let span = (hi as i64 - lo as i64 + 1) as u64;
+        (lo as i64 + (self.next() % span) as i64) as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stdlib;
+
+    #[test]
+    fn check_words() {
+        let state = &mut State::new();
+        stdlib(state).unwrap();
+        state.run("USE std:").unwrap();
+
+        state.run("check 2dup check swap check drop").unwrap();
+        state.run(": checked ( -- ) check dup ; checked").unwrap();
+        assert!(state.run("check call").is_err());
+
+        state.add_native_word("bad-2dup", "(a -- a a)", None, |state| {
+            state.over()?;
+            state.over()
+        });
+        let err = state.run("check bad-2dup").unwrap_err().to_string();
+        assert!(err.starts_with("Assertion Failed: check bad-2dup (a -- a a) failed for inputs [0]: expected 2 outputs on top of the stack, but found [\"<below>\", 0, \"<below>\", 0]"), "{}", err);
+
+        state
+            .run(": gens ( -- t ) {} [ 1 1000 random-int ] set_attr a ;")
+            .unwrap();
+        let err = state
+            .run("\"dup\" lookup gens [ 0 == ] check-with")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("failed for inputs [1]: property does not hold (seed "),
+            "{}",
+            err
+        );
+        state
+            .run("\"dup\" lookup None [ drop True ] check-with")
+            .unwrap();
+    }
+
+    #[test]
+    fn shrinking() {
+        assert_eq!(shrink(&Object::I32(0)).len(), 0);
+        let smaller: Vec<_> = shrink(&Object::I32(-5));
+        assert_eq!(smaller.len(), 3);
+        assert!(smaller[0] == 0 && smaller[1] == -2 && smaller[2] == -4);
+
+        let list = Object::List(Rc::new(vec![Object::I32(1), Object::I32(2)]));
+        let lengths: Vec<_> = shrink(&list)
+            .iter()
+            .map(|l| l.as_slice().unwrap().len())
+            .collect();
+        assert_eq!(lengths, vec![0, 1, 1, 1, 2, 2, 2]);
+
+        let rng = Rng::new(42);
+        assert!((0..100)
+            .map(|_| rng.range(-2, 2))
+            .all(|n| (-2..=2).contains(&n)));
+    }
+
+    #[test]
+    fn seeds() {
+        let state = &mut State::new();
+        stdlib(state).unwrap();
+        state.run("USE std:").unwrap();
+        let dup = state.lookup("dup").unwrap().unwrap();
+
+        let check = Check::new(dup.clone(), Object::None, None).unwrap();
+        let rng = Rng::new(1);
+        rng.seed(7);
+        let inputs = check.generate(state, &rng).unwrap();
+        rng.seed(7);
+        assert_eq!(check.generate(state, &rng).unwrap(), inputs);
+
+        state.run("[ drop False ]").unwrap();
+        let never = Check::new(dup, Object::None, Some(state.pop().unwrap())).unwrap();
+        let err = never.run_seeded(state, &rng, 7).unwrap_err().to_string();
+        assert!(
+            err.ends_with("failed for inputs [0]: property does not hold (seed 7)"),
+            "{}",
+            err
+        );
+    }
+}
@@ -44,26 +44,42 @@ impl From<Complex> for Object {
 pub fn complex(state: &mut State) -> Result<()> {
     // math operations
 
-    state.add_native_word("c_zero", "( -- x)", |state| {
-        state.push(Complex::new(0.0, 0.0))
-    });
-    state.add_native_word("c_one", "( -- x)", |state| {
-        state.push(Complex::new(1.0, 0.0))
-    });
-    state.add_native_word("c_i", "( -- x)", |state| state.push(Complex::new(0.0, 1.0)));
+    state.add_native_word(
+        "c_zero",
+        "( -- x)",
+        Some("Push the complex number 0."),
+        |state| state.push(Complex::new(0.0, 0.0)),
+    );
+    state.add_native_word(
+        "c_one",
+        "( -- x)",
+        Some("Push the complex number 1."),
+        |state| state.push(Complex::new(1.0, 0.0)),
+    );
+    state.add_native_word(
+        "c_i",
+        "( -- x)",
+        Some("Push the imaginary unit."),
+        |state| state.push(Complex::new(0.0, 1.0)),
+    );
 
-    state.add_native_word("c_add", "(a b -- c)", |state| {
-        let a = state.pop()?;
-        let b = state.pop()?;
-        match (a, b) {
-            (Object::Extension(a), Object::Extension(b)) => {
-                let a = a.as_any().downcast_ref::<Complex>().unwrap();
-                let b = b.as_any().downcast_ref::<Complex>().unwrap();
-                state.push(Complex::new(a.real + b.real, a.imag + b.imag))
+    state.add_native_word(
+        "c_add",
+        "(a b -- c)",
+        Some("Add two complex numbers."),
+        |state| {
+            let a = state.pop()?;
+            let b = state.pop()?;
+            match (a, b) {
+                (Object::Extension(a), Object::Extension(b)) => {
+                    let a = a.as_any().downcast_ref::<Complex>().unwrap();
+                    let b = b.as_any().downcast_ref::<Complex>().unwrap();
+                    state.push(Complex::new(a.real + b.real, a.imag + b.imag))
+                }
+                _ => panic!("expected complex"),
             }
-            _ => panic!("expected complex"),
-        }
-    });
+        },
+    );
 
     Ok(())
 }
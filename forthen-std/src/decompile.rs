@@ -0,0 +1,73 @@
+use forthen_core::objects::prelude::*;
+use forthen_core::parsing::escape;
+use forthen_core::{Entry, Object, State};
+use std::rc::Rc;
+
+/// Reconstruct forthen source code from a dictionary entry
+pub fn decompile(state: &State, entry: &Entry) -> String {
+    let body = match (entry.body(), entry.is_parsing()) {
+        (None, false) => return format!("{} ({}) <native>", entry.name, entry.stack_effect),
+        (None, true) => return format!("{} <native parsing word>", entry.name),
+        (Some(body), _) => body,
+    };
+
+    let locals = entry.locals.as_deref();
+
+    let (header, body) = match locals {
+        _ if entry.is_parsing() => (format!("SYNTAX: {}", entry.name), body),
+        // strip the frame setup and teardown that `::` wraps around the body
+        Some(_) if body.len() >= 4 => (
+            format!(":: {} ({})", entry.name, entry.stack_effect),
+            &body[2..body.len() - 2],
+        ),
+        _ => (format!(": {} ({})", entry.name, entry.stack_effect), body),
+    };
+
+    let mut items = vec![header];
+    items.extend(format_sequence(state, body, locals));
+    items.push(";".to_string());
+    items.join(" ")
+}
+
+/// Reconstruct forthen source code for a single object, e.g. a quotation
+pub fn decompile_object(state: &State, obj: &Object) -> String {
+    format_object(state, obj, None)
+}
+
+fn format_sequence(state: &State, seq: &[Object], locals: Option<&[String]>) -> Vec<String> {
+    let mut items = vec![];
+    let mut seq = seq.iter().peekable();
+
+    while let Some(obj) = seq.next() {
+        if let (Some(locals), Object::I32(i), Some(Object::Word(next))) = (locals, obj, seq.peek())
+        {
+            let keyword = match next.name.as_str() {
+                "store" => Some("set"),
+                "fetch" => Some("get"),
+                _ => None,
+            };
+            if let (Some(keyword), Some(var)) = (keyword, locals.get(*i as usize)) {
+                items.push(format!("{} {}", keyword, var));
+                seq.next();
+                continue;
+            }
+        }
+
+        items.push(format_object(state, obj, locals));
+    }
+
+    items
+}
+
+fn format_object(state: &State, obj: &Object, locals: Option<&[String]>) -> String {
+    match obj {
+        Object::List(list) if list.is_empty() => "[ ]".to_string(),
+        Object::List(list) => format!("[ {} ]", format_sequence(state, list, locals).join(" ")),
+        Object::Word(entry) => match state.current_module.lookup(&entry.name) {
+            Some(ref visible) if Rc::ptr_eq(visible, entry) => entry.name.to_string(),
+            _ => entry.qualified_name(),
+        },
+        Object::String(s) => format!("\"{}\"", escape(s)),
+        _ => obj.repr_sys(),
+    }
+}
@@ -0,0 +1,82 @@
+use forthen_core::errors::*;
+use forthen_core::{Object, State};
+
+/// Load words that read from the state's input into the dictionary
+pub fn input(state: &mut State) -> Result<()> {
+    state.new_mod("input".to_string())?;
+
+    state.add_native_word(
+        "read-line",
+        "( -- ?s)",
+        Some("Read the next line of input without its line terminator. Push None at end of input."),
+        |state| match state.input().read_line()? {
+            Some(line) => state.push_string(line),
+            None => state.push(Object::None),
+        },
+    );
+
+    state.add_native_word(
+        "read-all",
+        "( -- s)",
+        Some("Read all remaining input."),
+        |state| {
+            let text = state.input().read_all()?;
+            state.push_string(text)
+        },
+    );
+
+    state.add_native_word(
+        "key",
+        "( -- ?c)",
+        Some(
+            "Read a single character of input and push its code point. Push None at end of input.",
+        ),
+        |state| match state.input().read_char()? {
+            Some(ch) => state.push(ch as i32),
+            None => state.push(Object::None),
+        },
+    );
+
+    state.add_native_word(
+        "eof?",
+        "( -- ?)",
+        Some("Test if all input has been read."),
+        |state| {
+            let eof = state.input().is_eof()?;
+            state.push(eof)
+        },
+    );
+
+    state.exit_mod().unwrap();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tier0;
+    use forthen_core::Input;
+
+    #[test]
+    fn read_input() {
+        let state = &mut State::new();
+        tier0(state).unwrap();
+        input(state).unwrap();
+        state.run("USE input:").unwrap();
+
+        state.set_input(Input::from_string("first line\r\nsecond\näb\nrest\nof it"));
+
+        state.assert_run_pop("read-line", &["first line"]);
+        state.assert_run_pop("read-line", &["second"]);
+        state.assert_run_pop("key key key", &[228, 98, 10]);
+        state.run("eof?").unwrap();
+        assert!(!state.pop_bool().unwrap());
+        state.assert_run_pop("read-all", &["rest\nof it"]);
+
+        state.run("eof?").unwrap();
+        assert!(state.pop_bool().unwrap());
+        state.assert_run_pop("read-line key", &[Object::None, Object::None]);
+        state.assert_run_pop("read-all", &[""]);
+    }
+}
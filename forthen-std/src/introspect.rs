@@ -0,0 +1,534 @@
+use crate::decompile::decompile;
+use forthen_core::errors::*;
+use forthen_core::{Entry, ModuleRef, Object, State, WordId};
+use std::rc::Rc;
+
+/// Load dictionary introspection words into the dictionary
+pub fn introspect(state: &mut State) -> Result<()> {
+    state.new_mod("introspect".to_string())?;
+
+    state.add_native_word(
+        "words-in",
+        "(path -- names)",
+        Some("Push a list of the names of all words in the module at the given path."),
+        |state| {
+            let module = pop_module(state)?;
+            let mut names = module.local_keys();
+            names.sort();
+            let names = names
+                .into_iter()
+                .map(|name| Object::String(name.into()))
+                .collect();
+            state.push(Object::List(Rc::new(names)))
+        },
+    );
+
+    state.add_native_word(
+        "submodules",
+        "(path -- names)",
+        Some("Push a list of the names of all submodules of the module at the given path."),
+        |state| {
+            let module = pop_module(state)?;
+            let mut names = module.submodule_names();
+            names.sort();
+            let names = names
+                .into_iter()
+                .map(|name| Object::String(Rc::new(name)))
+                .collect();
+            state.push(Object::List(Rc::new(names)))
+        },
+    );
+
+    state.add_native_word(
+        "effect-of",
+        "(word -- s)",
+        Some(
+            "Push the stack effect of a word as a string. The word is given as word object or by \
+             name.",
+        ),
+        |state| {
+            let entry = pop_entry(state)?;
+            state.push_string(format!("({})", entry.stack_effect))
+        },
+    );
+
+    state.add_native_word(
+        "body-of",
+        "(word -- ?body)",
+        Some("Push the compiled body of a compound word, or None for native words."),
+        |state| {
+            let entry = pop_entry(state)?;
+            match entry.body() {
+                Some(body) => state.push(Object::List(Rc::new(body.to_vec()))),
+                None => state.push(Object::None),
+            }
+        },
+    );
+
+    state.add_native_word(
+        "native?",
+        "(word -- ?)",
+        Some("Test if a word is implemented natively."),
+        |state| {
+            let entry = pop_entry(state)?;
+            state.push(entry.is_native())
+        },
+    );
+
+    state.add_native_word(
+        "parsing?",
+        "(word -- ?)",
+        Some("Test if a word is a parsing word."),
+        |state| {
+            let entry = pop_entry(state)?;
+            state.push(entry.is_parsing())
+        },
+    );
+
+    state.add_native_word(
+        "source-of",
+        "(word -- ?s)",
+        Some("Push the source code of a word's definition, or None if it is unknown."),
+        |state| {
+            let entry = pop_entry(state)?;
+            match entry.location {
+                Some(ref location) => state.push_str(location.text()),
+                None => state.push(Object::None),
+            }
+        },
+    );
+
+    state.add_native_word(
+        "edit-location",
+        "(word -- ?loc)",
+        Some("Push the location of a word's definition as `file:line`, or None if it is unknown."),
+        |state| {
+            let entry = pop_entry(state)?;
+            match entry.location {
+                Some(ref location) => {
+                    let (line, _) = location.lines();
+                    state.push_string(format!("{}:{}", location.source.name, line))
+                }
+                None => state.push(Object::None),
+            }
+        },
+    );
+
+    state.add_native_word(
+        "decompile",
+        "(word -- s)",
+        Some("Reconstruct the source code of a word from its compiled body."),
+        |state| {
+            let entry = pop_entry(state)?;
+            let source = decompile(state, &entry);
+            state.push_string(source)
+        },
+    );
+
+    state.add_native_parse_word(
+        "see",
+        Some("`see word` prints the reconstructed source code of a word."),
+        |state| {
+            let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+            let entry = state.lookup(&name)?.ok_or(ErrorKind::UnknownWord(name))?;
+            writeln!(state.output(), "{}", decompile(state, &entry))?;
+            Ok(())
+        },
+    );
+
+    state.add_native_word(
+        "doc-of",
+        "(word -- ?s)",
+        Some("Push the doc string of a word, or None if it is undocumented."),
+        |state| {
+            let entry = pop_entry(state)?;
+            match entry.doc {
+                Some(ref doc) => state.push_str(doc),
+                None => state.push(Object::None),
+            }
+        },
+    );
+
+    state.add_native_parse_word(
+        "help",
+        Some("`help word` prints the stack effect, module and documentation of a word."),
+        |state| {
+            let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+            let entry = state.lookup(&name)?.ok_or(ErrorKind::UnknownWord(name))?;
+            writeln!(state.output(), "{}", help_text(&entry))?;
+            Ok(())
+        },
+    );
+
+    state.add_native_parse_word(
+        "trace",
+        Some(
+            "`trace word` logs every call of word, with the stack before and after. \
+             `trace module:` logs calls of all words defined in the module.",
+        ),
+        |state| {
+            let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+            let target = trace_target(state, &name)?;
+            let tracer = state.tracer();
+            match target {
+                TraceTarget::Word(name) => tracer.trace_word(&name),
+                TraceTarget::Module(path) => tracer.trace_module(&path),
+            }
+            Ok(())
+        },
+    );
+
+    state.add_native_parse_word(
+        "untrace",
+        Some("`untrace word` or `untrace module:` stops logging calls started with `trace`."),
+        |state| {
+            let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+            let name = match trace_target(state, &name)? {
+                TraceTarget::Word(name) | TraceTarget::Module(name) => name,
+            };
+            state.tracer().untrace(&name);
+            state.remove_idle_tracer();
+            Ok(())
+        },
+    );
+
+    state.add_native_word(
+        "trace-all",
+        "( -- )",
+        Some("Log calls of all words."),
+        |state| {
+            state.tracer().trace_all();
+            Ok(())
+        },
+    );
+
+    state.add_native_word(
+        "untrace-all",
+        "( -- )",
+        Some("Stop logging word calls."),
+        |state| {
+            state.tracer().untrace_all();
+            state.remove_idle_tracer();
+            Ok(())
+        },
+    );
+
+    state.exit_mod().unwrap();
+
+    Ok(())
+}
+
+enum TraceTarget {
+    /// qualified name of a word
+    Word(String),
+    /// module path
+    Module(String),
+}
+
+/// Resolve the argument of `trace` to a word, or to a module if it ends with a colon and is not
+/// the name of a word.
+fn trace_target(state: &State, name: &str) -> Result<TraceTarget> {
+    if let Some(entry) = state.lookup(name)? {
+        return Ok(TraceTarget::Word(entry.qualified_name()));
+    }
+    if name.ends_with(':') {
+        let module = state.lookup_module(name)?;
+        return Ok(TraceTarget::Module(module.path()));
+    }
+    Err(ErrorKind::UnknownWord(name.to_string()).into())
+}
+
+fn help_text(entry: &Entry) -> String {
+    let mut text = if entry.is_parsing() {
+        format!("{}  (parsing word)\n", entry.name)
+    } else {
+        format!("{}  ({})\n", entry.name, entry.stack_effect)
+    };
+
+    text += &format!("  module  :{}\n", entry.module);
+    if let Some(ref location) = entry.location {
+        text += &format!("  defined at {}\n", location);
+    }
+
+    match entry.doc {
+        Some(ref doc) => {
+            for line in doc.lines() {
+                text += &format!("\n  {}", line);
+            }
+        }
+        None => text += "\n  (undocumented)",
+    }
+
+    text
+}
+
+/// Pop a word from the stack, given either as word object or by name.
+fn pop_entry(state: &mut State) -> Result<WordId> {
+    match state.pop()? {
+        Object::Word(entry) => {
+            state.check_word(&entry)?;
+            Ok(entry)
+        }
+        Object::String(name) => state
+            .lookup(&name)?
+            .ok_or_else(|| ErrorKind::UnknownWord(name.to_string()).into()),
+        obj => Err(ErrorKind::TypeError(format!("{:?} is not a word", obj)).into()),
+    }
+}
+
+fn pop_module(state: &mut State) -> Result<ModuleRef> {
+    let path = state.pop_string()?;
+    state.lookup_module(&path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scope;
+    use crate::tier0;
+    use forthen_core::objects::prelude::*;
+    use forthen_core::{Output, Source};
+
+    #[test]
+    fn inspect_words() {
+        let state = &mut State::new();
+        tier0(state).unwrap();
+        introspect(state).unwrap();
+        state.run("USE introspect:").unwrap();
+
+        state.run("MODULE outer").unwrap();
+        state.run(": foo ( x -- y ) 1 ;").unwrap();
+        state.run(": bar ( -- ) ;").unwrap();
+        state.run("MODULE inner END-MODULE").unwrap();
+        state.run("END-MODULE").unwrap();
+
+        state.run("\"outer\" words-in").unwrap();
+        let names = state.pop().unwrap();
+        assert_eq!(names.as_slice().unwrap(), &["END-MODULE", "bar", "foo"]);
+
+        state.run("\":outer:\" submodules").unwrap();
+        let names = state.pop().unwrap();
+        assert_eq!(names.as_slice().unwrap(), &["inner"]);
+
+        assert!(state.run("\"nowhere\" words-in").is_err());
+
+        state.run("USE outer:foo").unwrap();
+        state.assert_run_pop("\"foo\" effect-of", &["(x -- y)"]);
+
+        state.run("\"foo\" lookup native?").unwrap();
+        assert!(!state.pop_bool().unwrap());
+        state.run("\"foo\" parsing?").unwrap();
+        assert!(!state.pop_bool().unwrap());
+        state.run("\"call\" native?").unwrap();
+        assert!(state.pop_bool().unwrap());
+        state.run("\":\" parsing?").unwrap();
+        assert!(state.pop_bool().unwrap());
+
+        state.run("\"foo\" body-of").unwrap();
+        let body = state.pop().unwrap();
+        assert_eq!(body.as_slice().unwrap(), &[1]);
+
+        state.assert_run_pop("\"call\" body-of", &[Object::None]);
+
+        state.run("SYNTAX: answer 42 ;").unwrap();
+        state.run("\"answer\" body-of").unwrap();
+        let body = state.pop().unwrap();
+        assert_eq!(body.as_slice().unwrap(), &[42]);
+    }
+
+    #[test]
+    fn definition_locations() {
+        let state = &mut State::new();
+        tier0(state).unwrap();
+        scope(state).unwrap();
+        introspect(state).unwrap();
+        state.run("USE introspect:").unwrap();
+        state.run("USE scope:").unwrap();
+
+        let code = "
+            : one ( -- x )
+                1 ;
+            :: two ( -- x ) 2 set x get x ;
+            SYNTAX: three 3 ;  LET: four 4 ;
+        ";
+        state.run_source(Source::new("defs.fn", code)).unwrap();
+
+        state.assert_run_pop(
+            "\"one\" source-of",
+            &[": one ( -- x )\n                1 ;"],
+        );
+        state.assert_run_pop("\"one\" edit-location", &["defs.fn:2"]);
+        state.assert_run_pop("\"two\" source-of", &[":: two ( -- x ) 2 set x get x ;"]);
+        state.assert_run_pop("\"three\" source-of", &["SYNTAX: three 3 ;"]);
+        state.assert_run_pop("\"four\" source-of", &["LET: four 4 ;"]);
+        state.assert_run_pop("\"four\" edit-location", &["defs.fn:5"]);
+
+        let entry = state.current_module.lookup("one").unwrap();
+        assert_eq!(entry.location.as_ref().unwrap().lines(), (2, 3));
+        assert_eq!(
+            format!("{}", entry.location.as_ref().unwrap()),
+            "defs.fn:2-3"
+        );
+
+        state.assert_run_pop("\"call\" source-of", &[Object::None]);
+    }
+
+    #[test]
+    fn documentation() {
+        let state = &mut State::new();
+        tier0(state).unwrap();
+        scope(state).unwrap();
+        introspect(state).unwrap();
+        state.run("USE introspect:").unwrap();
+        state.run("USE scope:").unwrap();
+
+        state
+            .run(": sq (x -- x2) \"Square a number.\" DOC 42 ;")
+            .unwrap();
+        state.assert_run_pop("\"sq\" doc-of", &["Square a number."]);
+        state.assert_run_pop("sq", &[42]);
+
+        state
+            .run(
+                ":: cube (x -- x3) ((
+                        Cube a number.
+                        (Multiplies x with itself, twice.)
+                    )) get x ;",
+            )
+            .unwrap();
+        state.assert_run_pop(
+            "\"cube\" doc-of",
+            &["Cube a number.\n(Multiplies x with itself, twice.)"],
+        );
+
+        state.run("SYNTAX: nothing ((Does nothing.)) ;").unwrap();
+        state.assert_run_pop("\"nothing\" doc-of", &["Does nothing."]);
+
+        state.run(": plain ( -- ) ( not a doc comment ) ;").unwrap();
+        state.assert_run_pop("\"plain\" doc-of", &[Object::None]);
+
+        // doc strings belong to the definition or quotation they are compiled in
+        assert!(state
+            .run(": broken ( -- ) \"Broken.\" DOC nosuchword ;")
+            .is_err());
+        state.run(": after ( -- ) ;").unwrap();
+        state.assert_run_pop("\"after\" doc-of", &[Object::None]);
+        state.run(": outer ( -- q ) [ \"Inner.\" DOC ] ;").unwrap();
+        state.assert_run_pop("\"outer\" doc-of", &[Object::None]);
+        assert!(state.run("\"Nowhere.\" DOC").is_err());
+
+        state.assert_run_pop(
+            "\"doc-of\" doc-of",
+            &["Push the doc string of a word, or None if it is undocumented."],
+        );
+
+        let entry = state.current_module.lookup("sq").unwrap();
+        assert_eq!(
+            help_text(&entry),
+            "sq  (x -- x2)\n  module  :\n  defined at <input>:1\n\n  Square a number."
+        );
+    }
+
+    #[test]
+    fn decompile_words() {
+        let state = &mut State::new();
+        tier0(state).unwrap();
+        scope(state).unwrap();
+        introspect(state).unwrap();
+        state.run("USE introspect:").unwrap();
+        state.run("USE scope:").unwrap();
+
+        state.run(": quoted ( -- q ) [ 1 \"a\\n\" [ ] ] ;").unwrap();
+        state.assert_run_pop(
+            "\"quoted\" decompile",
+            &[": quoted ( -- q) [ 1 \"a\\n\" [ ] ] ;"],
+        );
+
+        // string literals read back as the same string
+        state
+            .run(r#": escaped ( -- s ) "say \"hi\"\tC:\\" ;"#)
+            .unwrap();
+        state.run("\"escaped\" decompile").unwrap();
+        let source = state.pop_string().unwrap();
+        assert_eq!(source, r#": escaped ( -- s) "say \"hi\"\tC:\\" ;"#);
+        state.run(&source.replace("escaped", "again")).unwrap();
+        state.run("escaped again").unwrap();
+        assert_eq!(state.pop_string().unwrap(), "say \"hi\"\tC:\\");
+        assert_eq!(state.pop_string().unwrap(), "say \"hi\"\tC:\\");
+
+        state
+            .run(":: flip (a b -- b a) set b set a [ get b ] call get a ;")
+            .unwrap();
+        state.assert_run_pop(
+            "\"flip\" decompile",
+            &[":: flip (a b -- b a) set b set a [ get b ] call get a ;"],
+        );
+
+        state.run("SYNTAX: answer 42 ;").unwrap();
+        state.assert_run_pop("\"answer\" decompile", &["SYNTAX: answer 42 ;"]);
+
+        state.assert_run_pop(
+            "\"call\" decompile",
+            &["call (..a func(..a -- ..b) -- ..b) <native>"],
+        );
+
+        state
+            .run("MODULE inner : foo ( -- x ) 1 ; END-MODULE")
+            .unwrap();
+        state.run("USE inner:foo").unwrap();
+        state.run(": bar ( -- x ) foo ;").unwrap();
+        state.assert_run_pop("\"bar\" decompile", &[": bar ( -- x) foo ;"]);
+        state.run(": foo ( -- x ) 2 ;").unwrap();
+        state.assert_run_pop("\"bar\" decompile", &[": bar ( -- x) :inner:foo ;"]);
+
+        // the output of `see` reads back as an equivalent word, even for shadowed words
+        let (output, buffer) = Output::capture();
+        state.set_output(output);
+        state.run("see bar").unwrap();
+        let source = buffer.contents();
+        state.run(&source.replace("bar", "bar2")).unwrap();
+        state.assert_run_pop("bar bar2", &[1, 1]);
+        assert!(state.run(":nowhere:foo").is_err());
+    }
+
+    #[test]
+    fn tracing() {
+        let state = &mut State::new();
+        tier0(state).unwrap();
+        introspect(state).unwrap();
+        state.run("USE introspect:").unwrap();
+        state
+            .run("MODULE m : one ( -- x ) 1 ; : two ( -- x y ) one one ; END-MODULE USE m:")
+            .unwrap();
+
+        let (output, buffer) = Output::capture();
+        state.set_output(output);
+
+        state.run("trace two two untrace two two").unwrap();
+        state.run("trace m: two untrace m:").unwrap();
+        assert!(state.hooks().is_empty());
+        state
+            .run("trace : : three ( -- x ) 3 ; untrace-all three")
+            .unwrap();
+        assert!(state.hooks().is_empty());
+        state.assert_stack(&[1, 1, 1, 1, 1, 1, 3]);
+
+        assert_eq!(
+            buffer.contents(),
+            "> two  []
+< two  [1, 1]
+> two  [1, 1, 1, 1]
+  > one  [1, 1, 1, 1]
+  < one  [1, 1, 1, 1, 1]
+  > one  [1, 1, 1, 1, 1]
+  < one  [1, 1, 1, 1, 1, 1]
+< two  [1, 1, 1, 1, 1, 1]
+> : (parsing)  [1, 1, 1, 1, 1, 1]
+< : (parsing)  [1, 1, 1, 1, 1, 1]
+"
+        );
+
+        assert!(state.run("trace nothing").is_err());
+        assert!(state.run("trace nowhere:").is_err());
+    }
+}
@@ -0,0 +1,375 @@
+use forthen_core::errors::*;
+use forthen_core::objects::prelude::*;
+use forthen_core::{Object, State};
+use std::any::Any;
+use std::cell::RefCell;
+use std::fs;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::rc::Rc;
+
+/// An open file, either for reading or for writing
+struct FileHandle {
+    path: String,
+    file: RefCell<Option<OpenFile>>,
+}
+
+enum OpenFile {
+    Reader(BufReader<fs::File>),
+    Writer(BufWriter<fs::File>),
+}
+
+impl FileHandle {
+    fn new(path: String, file: OpenFile) -> Self {
+        FileHandle {
+            path,
+            file: RefCell::new(Some(file)),
+        }
+    }
+
+    fn read_line(&self) -> Result<Option<String>> {
+        match *self.file.borrow_mut() {
+            Some(OpenFile::Reader(ref mut reader)) => {
+                let mut line = String::new();
+                if reader
+                    .read_line(&mut line)
+                    .map_err(|e| io_error(&self.path, e))?
+                    == 0
+                {
+                    return Ok(None);
+                }
+                if line.ends_with('\n') {
+                    line.pop();
+                    if line.ends_with('\r') {
+                        line.pop();
+                    }
+                }
+                Ok(Some(line))
+            }
+            Some(OpenFile::Writer(_)) => Err(self.error("file is not open for reading")),
+            None => Err(self.error("file is closed")),
+        }
+    }
+
+    fn write(&self, text: &str) -> Result<()> {
+        match *self.file.borrow_mut() {
+            Some(OpenFile::Writer(ref mut writer)) => writer
+                .write_all(text.as_bytes())
+                .map_err(|e| io_error(&self.path, e)),
+            Some(OpenFile::Reader(_)) => Err(self.error("file is not open for writing")),
+            None => Err(self.error("file is closed")),
+        }
+    }
+
+    fn close(&self) -> Result<()> {
+        match self.file.borrow_mut().take() {
+            Some(OpenFile::Writer(mut writer)) => {
+                writer.flush().map_err(|e| io_error(&self.path, e))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn error(&self, msg: &str) -> Error {
+        ErrorKind::IoError(self.path.clone(), msg.to_string()).into()
+    }
+}
+
+impl ObjectInterface for FileHandle {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn repr_sys(&self) -> String {
+        match *self.file.borrow() {
+            Some(_) => format!("<file {:?}>", self.path),
+            None => format!("<closed file {:?}>", self.path),
+        }
+    }
+}
+
+impl From<FileHandle> for Object {
+    fn from(f: FileHandle) -> Self {
+        Object::Extension(Rc::new(f))
+    }
+}
+
+fn io_error(path: &str, e: std::io::Error) -> Error {
+    ErrorKind::IoError(path.to_string(), e.to_string()).into()
+}
+
+fn pop_handle(state: &mut State) -> Result<Rc<dyn ObjectInterface>> {
+    match state.pop()? {
+        Object::Extension(obj) if obj.as_any().is::<FileHandle>() => Ok(obj),
+        obj => Err(ErrorKind::TypeError(format!("{:?} is not a file", obj)).into()),
+    }
+}
+
+fn as_handle(obj: &Rc<dyn ObjectInterface>) -> &FileHandle {
+    obj.as_any().downcast_ref().unwrap()
+}
+
+fn push_strings(state: &mut State, strings: impl Iterator<Item = String>) -> Result<()> {
+    let list = strings.map(|s| Object::String(Rc::new(s))).collect();
+    state.push(Object::List(Rc::new(list)))
+}
+
+/// Load file system access into the dictionary.
+///
+/// This is not part of `stdlib`; interpreters that should be able to access files load it explicitly.
+pub fn io(state: &mut State) -> Result<()> {
+    state.new_mod("io".to_string())?;
+
+    state.add_native_word(
+        "file-read",
+        "(path -- s)",
+        Some("Read the whole content of a file."),
+        |state| {
+            let path = state.pop_string()?;
+            let text = fs::read_to_string(&path).map_err(|e| io_error(&path, e))?;
+            state.push_string(text)
+        },
+    );
+
+    state.add_native_word(
+        "file-write",
+        "(s path -- )",
+        Some("Write a string to a file, replacing its previous content."),
+        |state| {
+            let path = state.pop_string()?;
+            let text = state.pop_string()?;
+            fs::write(&path, text).map_err(|e| io_error(&path, e))
+        },
+    );
+
+    state.add_native_word(
+        "file-append",
+        "(s path -- )",
+        Some("Append a string to a file, creating it if necessary."),
+        |state| {
+            let path = state.pop_string()?;
+            let text = state.pop_string()?;
+            fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .and_then(|mut file| file.write_all(text.as_bytes()))
+                .map_err(|e| io_error(&path, e))
+        },
+    );
+
+    state.add_native_word(
+        "file-lines",
+        "(path -- lines)",
+        Some("Read a file as a list of lines."),
+        |state| {
+            let path = state.pop_string()?;
+            let text = fs::read_to_string(&path).map_err(|e| io_error(&path, e))?;
+            push_strings(state, text.lines().map(str::to_string))
+        },
+    );
+
+    state.add_native_word(
+        "file-exists?",
+        "(path -- ?)",
+        Some("Test if a file or directory exists."),
+        |state| {
+            let path = state.pop_string()?;
+            state.push(std::path::Path::new(&path).exists())
+        },
+    );
+
+    state.add_native_word(
+        "dir-list",
+        "(path -- names)",
+        Some("Push the sorted list of names in a directory."),
+        |state| {
+            let path = state.pop_string()?;
+            let mut names = fs::read_dir(&path)
+                .and_then(|entries| {
+                    entries
+                        .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+                        .collect::<std::io::Result<Vec<_>>>()
+                })
+                .map_err(|e| io_error(&path, e))?;
+            names.sort();
+            push_strings(state, names.into_iter())
+        },
+    );
+
+    state.add_native_word(
+        "mkdir",
+        "(path -- )",
+        Some("Create a directory and all missing parent directories."),
+        |state| {
+            let path = state.pop_string()?;
+            fs::create_dir_all(&path).map_err(|e| io_error(&path, e))
+        },
+    );
+
+    state.add_native_word(
+        "remove",
+        "(path -- )",
+        Some("Remove a file or an empty directory."),
+        |state| {
+            let path = state.pop_string()?;
+            let result = if std::path::Path::new(&path).is_dir() {
+                fs::remove_dir(&path)
+            } else {
+                fs::remove_file(&path)
+            };
+            result.map_err(|e| io_error(&path, e))
+        },
+    );
+
+    state.add_native_word(
+        "open-read",
+        "(path -- file)",
+        Some("Open a file for reading."),
+        |state| {
+            let path = state.pop_string()?;
+            let file = fs::File::open(&path).map_err(|e| io_error(&path, e))?;
+            state.push(FileHandle::new(
+                path,
+                OpenFile::Reader(BufReader::new(file)),
+            ))
+        },
+    );
+
+    state.add_native_word(
+        "open-write",
+        "(path -- file)",
+        Some("Open a file for writing, replacing its previous content."),
+        |state| {
+            let path = state.pop_string()?;
+            let file = fs::File::create(&path).map_err(|e| io_error(&path, e))?;
+            state.push(FileHandle::new(
+                path,
+                OpenFile::Writer(BufWriter::new(file)),
+            ))
+        },
+    );
+
+    state.add_native_word(
+        "open-append",
+        "(path -- file)",
+        Some("Open a file for appending, creating it if necessary."),
+        |state| {
+            let path = state.pop_string()?;
+            let file = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .map_err(|e| io_error(&path, e))?;
+            state.push(FileHandle::new(
+                path,
+                OpenFile::Writer(BufWriter::new(file)),
+            ))
+        },
+    );
+
+    state.add_native_word(
+        "close",
+        "(file -- )",
+        Some("Close a file. Pending writes are flushed."),
+        |state| as_handle(&pop_handle(state)?).close(),
+    );
+
+    state.add_native_word(
+        "file-read-line",
+        "(file -- ?s)",
+        Some(
+            "Read the next line of a file without its line terminator. Push None at the end of \
+             the file.",
+        ),
+        |state| match as_handle(&pop_handle(state)?).read_line()? {
+            Some(line) => state.push_string(line),
+            None => state.push(Object::None),
+        },
+    );
+
+    state.add_native_word(
+        "write",
+        "(s file -- )",
+        Some("Write a string to a file."),
+        |state| {
+            let file = pop_handle(state)?;
+            let text = state.pop_string()?;
+            as_handle(&file).write(&text)
+        },
+    );
+
+    state.exit_mod().unwrap();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{input, ops, stack, tier0};
+    use forthen_core::Input;
+
+    #[test]
+    fn files() {
+        let dir = std::env::temp_dir().join(format!("forthen-io-{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+
+        let state = &mut State::new();
+        tier0(state).unwrap();
+        ops(state).unwrap();
+        stack(state).unwrap();
+        input(state).unwrap();
+        io(state).unwrap();
+        state.run("USE ops:").unwrap();
+        state.run("USE stack:").unwrap();
+        state.run("USE input:").unwrap();
+        state.run("USE io:").unwrap();
+        state
+            .run(&format!(": dir ( -- path ) \"{}\" ;", dir))
+            .unwrap();
+        state.run(": sub ( -- path ) \"/sub/f.txt\" ;").unwrap();
+
+        state.run("dir sub + file-exists?").unwrap();
+        assert!(!state.pop_bool().unwrap());
+
+        state.run("dir \"/sub\" + mkdir").unwrap();
+        state.run("\"one\\n\" dir sub + file-write").unwrap();
+        state.run("\"two\\n\" dir sub + file-append").unwrap();
+        state.assert_run_pop("dir sub + file-read", &["one\ntwo\n"]);
+        state.run("dir sub + file-lines").unwrap();
+        assert_eq!(state.pop().unwrap().as_slice().unwrap(), &["one", "two"]);
+
+        state.run("dir sub + file-exists?").unwrap();
+        assert!(state.pop_bool().unwrap());
+
+        state.run("dir \"/sub/g.txt\" + open-write").unwrap();
+        state
+            .run("\"first\\n\" over write \"last\" over write close")
+            .unwrap();
+        state.run("dir \"/sub/g.txt\" + open-read").unwrap();
+        state.assert_run_pop("dup file-read-line", &["first"]);
+        state.assert_run_pop("dup file-read-line", &["last"]);
+        state.assert_run_pop("dup file-read-line", &[Object::None]);
+        state.run("dup close").unwrap();
+        assert!(state.run("file-read-line").is_err());
+
+        // reading from a file does not hide reading from the input
+        state.set_input(Input::from_string("typed\n"));
+        state.assert_run_pop("read-line", &["typed"]);
+
+        state.run("dir \"/sub\" + dir-list").unwrap();
+        assert_eq!(
+            state.pop().unwrap().as_slice().unwrap(),
+            &["f.txt", "g.txt"]
+        );
+
+        state.run("dir sub + remove").unwrap();
+        state.run("dir \"/sub/g.txt\" + remove").unwrap();
+        state.run("dir \"/sub\" + remove dir remove").unwrap();
+
+        match state.run("dir file-read") {
+            Err(Error(ErrorKind::IoError(path, _), _)) => assert_eq!(path, dir),
+            other => panic!("expected io error, got {:?}", other),
+        }
+    }
+}
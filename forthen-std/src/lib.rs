@@ -1,31 +1,45 @@
 mod branch;
+mod check;
 mod complex;
+mod decompile;
+mod input;
+mod introspect;
+mod io;
 mod list;
 mod loops;
 mod ops;
 mod scope;
 mod stack;
 mod stdlib;
+mod sys;
 mod table;
+mod testing;
 mod tier0;
 mod timeit;
 
 pub use branch::branch;
+pub use check::check;
 pub use complex::complex;
+pub use input::input;
+pub use introspect::introspect;
+pub use io::io;
 pub use list::list;
 pub use loops::loops;
 pub use ops::ops;
 pub use scope::scope;
 pub use stack::stack;
 pub use stdlib::stdlib;
+pub use sys::sys;
 pub use table::table;
+pub use testing::{collect_tests, testing};
 pub use tier0::tier0;
 pub use timeit::timeit;
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use forthen_core::State;
+    use forthen_core::errors::*;
+    use forthen_core::{Object, Sandbox, State};
 
     #[test]
     fn recursion() {
@@ -66,4 +80,81 @@ mod tests {
         assert_eq!(3628800, state.pop_i32().unwrap());
         assert_eq!("guard", state.pop_string().unwrap());
     }
+
+    fn denied(result: Result<()>) -> bool {
+        matches!(result, Err(Error(ErrorKind::AccessDenied(_), _)))
+    }
+
+    #[test]
+    fn sandbox() {
+        let mut state = State::new();
+        stdlib(&mut state).unwrap();
+        io(&mut state).unwrap();
+
+        let sb = &mut state.sandboxed(Sandbox::pure().allow_word("std:sys", "args"));
+        sb.run("USE std:").unwrap();
+        sb.assert_run_pop("1 2 + [ 1 ] 0 list-get swap drop", &[3, 1]);
+
+        // whole-module imports skip denied words
+        assert!(sb.run("timeit").is_err());
+        sb.run("USE :std:sys:").unwrap();
+        sb.run("args").unwrap();
+        assert!(sb.run("exit").is_err());
+
+        assert!(denied(sb.run("USE :std:sys:exit")));
+        assert!(denied(sb.run("USE :io:file-read")));
+        assert!(denied(sb.run("USE :io:")));
+        assert!(denied(sb.run("\":std:timeit\" words-in")));
+        assert!(denied(sb.run("INCLUDE some-file.fn")));
+        assert!(denied(sb.run("MODULE std")));
+
+        let exit = state
+            .lookup_module(":std:sys")
+            .unwrap()
+            .lookup("exit")
+            .unwrap();
+        sb.push(Object::Word(exit)).unwrap();
+        assert!(denied(sb.run("call")));
+
+        sb.run("MODULE mine : five ( -- x ) 5 ; END-MODULE")
+            .unwrap();
+        sb.assert_run_pop("USE mine:five five", &[5]);
+
+        // the parent state is not restricted
+        state.run("USE :std:sys:exit").unwrap();
+        state.run("USE :io:").unwrap();
+    }
+
+    #[test]
+    fn sandbox_escapes() {
+        let mut state = State::new();
+        stdlib(&mut state).unwrap();
+        io(&mut state).unwrap();
+        state.run("USE :std: USE :io:").unwrap();
+        state.run(": leave ( -- ) 7 exit ;").unwrap();
+
+        let sb = &mut state.sandboxed(Sandbox::pure());
+
+        // denied words can not be compiled or called indirectly
+        assert!(denied(sb.run("[ 7 DELAY exit ] call")));
+        assert!(denied(sb.run(
+            "[ \"pwned\" \"/nonexistent/pwned.txt\" DELAY file-write ] call"
+        )));
+        sb.stack.clear();
+        assert!(denied(sb.run("leave")));
+        assert!(denied(sb.run("7 :std:sys:exit")));
+        sb.stack.clear();
+
+        // definitions stay inside the sandbox
+        sb.run(": dup ( a -- a a ) \"hijacked\" ;").unwrap();
+        sb.run("1 dup").unwrap();
+        assert_eq!(sb.pop_string().unwrap(), "hijacked");
+        state.assert_run_pop("1 dup", &[1, 1]);
+        assert!(state
+            .lookup("dup")
+            .unwrap()
+            .unwrap()
+            .module
+            .starts_with("std"));
+    }
 }
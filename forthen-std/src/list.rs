@@ -8,85 +8,125 @@ use std::rc::Rc;
 pub fn list(state: &mut State) -> Result<()> {
     state.new_mod("list".to_string())?;
 
-    state.add_native_word("list-make", "(* n -- l)", |state| {
-        let n = state.pop_i32()?;
-        let mut list = vec![];
-        for _ in 0..n {
-            list.push(state.pop()?);
-        }
-        list.reverse();
-        state.push(Object::List(Rc::new(list)))
-    });
+    state.add_native_word(
+        "list-make",
+        "(* n -- l)",
+        Some("Make a list from the top n items on the stack."),
+        |state| {
+            let n = state.pop_i32()?;
+            let mut list = vec![];
+            for _ in 0..n {
+                list.push(state.pop()?);
+            }
+            list.reverse();
+            state.push(Object::List(Rc::new(list)))
+        },
+    );
 
-    state.add_native_word("list-destructure", "(l -- * n)", |state| {
-        let rclist = state.pop()?.into_rc_vec()?;
-        let n = rclist.len();
-        match Rc::try_unwrap(rclist) {
-            Ok(mut list) => {
-                for x in list.drain(..) {
-                    state.push(x)?;
+    state.add_native_word(
+        "list-destructure",
+        "(l -- * n)",
+        Some("Push all items of a list, followed by their number."),
+        |state| {
+            let rclist = state.pop()?.into_rc_vec()?;
+            let n = rclist.len();
+            match Rc::try_unwrap(rclist) {
+                Ok(mut list) => {
+                    for x in list.drain(..) {
+                        state.push(x)?;
+                    }
                 }
-            }
-            Err(rclist) => {
-                for x in rclist.iter() {
-                    state.push(x.clone())?;
+                Err(rclist) => {
+                    for x in rclist.iter() {
+                        state.push(x.clone())?;
+                    }
                 }
             }
-        }
-        state.push(n as i32)?;
-        Ok(())
-    });
+            state.push(n as i32)?;
+            Ok(())
+        },
+    );
 
-    state.add_native_word("list-get", "(l i -- l x)", |state| {
-        let i = state.pop_i32()? as usize;
-        let x = {
-            let list = state.top()?.as_slice()?;
-            list.get(i)
-                .ok_or_else(|| ErrorKind::IndexError(i, list.len()))?
-                .clone()
-        };
-        state.push(x)
-    });
+    state.add_native_word(
+        "list-get",
+        "(l i -- l x)",
+        Some("Get the item at index i of the list."),
+        |state| {
+            let i = state.pop_i32()? as usize;
+            let x = {
+                let list = state.top()?.as_slice()?;
+                list.get(i)
+                    .ok_or_else(|| ErrorKind::IndexError(i, list.len()))?
+                    .clone()
+            };
+            state.push(x)
+        },
+    );
 
-    state.add_native_word("list-set", "(l i x -- l')", |state| {
-        let i = state.pop_i32()? as usize;
-        let x = state.pop()?;
-        let list = state.top_mut()?.as_vec_mut()?;
-        let len = list.len();
-        *list
-            .get_mut(i)
-            .ok_or_else(|| ErrorKind::IndexError(i, len))? = x;
-        Ok(())
-    });
+    state.add_native_word(
+        "list-set",
+        "(l i x -- l')",
+        Some("Replace the item at index i of the list with x."),
+        |state| {
+            let i = state.pop_i32()? as usize;
+            let x = state.pop()?;
+            let list = state.top_mut()?.as_vec_mut()?;
+            let len = list.len();
+            *list
+                .get_mut(i)
+                .ok_or_else(|| ErrorKind::IndexError(i, len))? = x;
+            Ok(())
+        },
+    );
 
-    state.add_native_word("pop-back", "(l -- l' x)", |state| {
-        let list = state.top_mut()?.as_vec_mut()?;
-        let x = list.pop().ok_or_else(|| ErrorKind::IndexError(0, 0))?;
-        state.push(x)
-    });
+    state.add_native_word(
+        "pop-back",
+        "(l -- l' x)",
+        Some("Remove the last item from the list."),
+        |state| {
+            let list = state.top_mut()?.as_vec_mut()?;
+            let x = list.pop().ok_or_else(|| ErrorKind::IndexError(0, 0))?;
+            state.push(x)
+        },
+    );
 
-    state.add_native_word("pop-front", "(l -- l' x)", |state| {
-        let list = state.top_mut()?.as_vec_mut()?;
-        if list.is_empty() {
-            return Err(ErrorKind::IndexError(0, 0).into());
-        }
-        let x = list.remove(0);
-        state.push(x)
-    });
+    state.add_native_word(
+        "pop-front",
+        "(l -- l' x)",
+        Some("Remove the first item from the list."),
+        |state| {
+            let list = state.top_mut()?.as_vec_mut()?;
+            if list.is_empty() {
+                return Err(ErrorKind::IndexError(0, 0).into());
+            }
+            let x = list.remove(0);
+            state.push(x)
+        },
+    );
 
-    state.add_native_word("push-back", "(l x -- l')", |state| {
-        let x = state.pop()?;
-        let list = state.top_mut()?.as_vec_mut()?;
-        list.push(x);
-        Ok(())
-    });
+    state.add_native_word(
+        "push-back",
+        "(l x -- l')",
+        Some("Append an item to the end of the list."),
+        |state| {
+            let x = state.pop()?;
+            let list = state.top_mut()?.as_vec_mut()?;
+            list.push(x);
+            Ok(())
+        },
+    );
 
-    state.add_native_word("push-front", "(l x -- l')", |state| {
-        let x = state.pop()?;
-        let list = state.top_mut()?.as_vec_mut()?;
-        list.insert(0, x);
-        Ok(())
-    });
+    state.add_native_word(
+        "push-front",
+        "(l x -- l')",
+        Some("Insert an item at the beginning of the list."),
+        |state| {
+            let x = state.pop()?;
+            let list = state.top_mut()?.as_vec_mut()?;
+            list.insert(0, x);
+            Ok(())
+        },
+    );
 
     state.exit_mod().unwrap();
 
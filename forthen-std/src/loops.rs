@@ -6,29 +6,40 @@ use forthen_core::State;
 pub fn loops(state: &mut State) -> Result<()> {
     state.new_mod("loop".to_string())?;
 
-    state.add_native_word("repeat", "(..x n f(..x -- ..y) -- ..y)", |state| {
-        let callee = state.pop()?;
-        let n = state.pop_i32()?;
-        for _ in 0..n {
-            callee.call(state)?;
-        }
-        Ok(())
-    });
-
-    state.add_native_word("for", "(..x a b f(..x i -- ..y) -- ..y)", |state| {
-        let callee = state.pop()?;
-        let b = state.pop_i32()?;
-        let a = state.pop_i32()?;
-        for i in a..b {
-            state.push(i)?;
-            callee.call(state)?;
-        }
-        Ok(())
-    });
+    state.add_native_word(
+        "repeat",
+        "(..x n f(..x -- ..y) -- ..y)",
+        Some("Call f n times."),
+        |state| {
+            let callee = state.pop()?;
+            let n = state.pop_i32()?;
+            for _ in 0..n {
+                callee.call(state)?;
+            }
+            Ok(())
+        },
+    );
+
+    state.add_native_word(
+        "for",
+        "(..x a b f(..x i -- ..y) -- ..y)",
+        Some("Call f for each i in the range a..b, with i on top of the stack. b is exclusive."),
+        |state| {
+            let callee = state.pop()?;
+            let b = state.pop_i32()?;
+            let a = state.pop_i32()?;
+            for i in a..b {
+                state.push(i)?;
+                callee.call(state)?;
+            }
+            Ok(())
+        },
+    );
 
     state.add_native_word(
         "while",
         "(..a cond(..a -- ..b ?) f(..b -- ..c) -- ..c)",
+        Some("Call f as long as cond returns true."),
         |state| {
             let callee = state.pop()?;
             let cond = state.pop()?;
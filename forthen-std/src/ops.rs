@@ -1,49 +1,168 @@
 use forthen_core::errors::*;
 use forthen_core::objects::prelude::*;
 use forthen_core::Object;
+use forthen_core::Output;
 use forthen_core::State;
 
 /// Load basic operations into the dictionary
 pub fn ops(state: &mut State) -> Result<()> {
     state.new_mod("ops".to_string())?;
 
-    state.add_native_word("emit", "(x -- )", |state| {
-        match state.pop()? {
-            Object::String(s) => print!("{}", s),
-            Object::I32(i) => println!("{}", std::char::from_u32(i as u32).unwrap_or('�')),
-            obj => return Err(ErrorKind::TypeError(format!("Can't emit {:?}", obj)).into()),
-        }
-        Ok(())
-    });
-
-    state.add_native_word("repr", "(x -- s)", |state| state.pop()?.repr(state));
+    state.add_native_word(
+        "emit",
+        "(x -- )",
+        Some("Print a string, or the character with the given code point."),
+        |state| {
+            match state.pop()? {
+                Object::String(s) => write!(state.output(), "{}", s)?,
+                Object::I32(i) => writeln!(
+                    state.output(),
+                    "{}",
+                    std::char::from_u32(i as u32).unwrap_or('�')
+                )?,
+                obj => return Err(ErrorKind::TypeError(format!("Can't emit {:?}", obj)).into()),
+            }
+            Ok(())
+        },
+    );
 
-    state.add_native_word("same?", "(a b -- ?)", |state| {
-        let b = state.pop()?;
-        let a = state.pop()?;
-        state.push(b.is_same(&a))
-    });
+    state.add_native_word(
+        "with-output-to-string",
+        "(..a quot(..a -- ..b) -- ..b s)",
+        Some("Call the quotation and push everything it printed as a string."),
+        |state| {
+            let quot = state.pop()?;
+            let (capture, buffer) = Output::capture();
+            let previous = state.set_output(capture);
+            let result = quot.call(state);
+            state.set_output(previous);
+            result?;
+            state.push_string(buffer.contents())
+        },
+    );
 
-    state.add_native_word("not", "(? -- !?)", |state| state.pop()?.not(state));
+    state.add_native_word(
+        "repr",
+        "(x -- s)",
+        Some("Convert an object into its string representation."),
+        |state| state.pop()?.repr(state),
+    );
 
-    state.add_native_word("==", "(a b -- ?)", |state| state.pop()?.is_eq(state));
+    state.add_native_word(
+        "same?",
+        "(a b -- ?)",
+        Some("Test if a and b are the same object."),
+        |state| {
+            let b = state.pop()?;
+            let a = state.pop()?;
+            state.push(b.is_same(&a))
+        },
+    );
 
-    state.add_native_word("!=", "(a b -- ?)", |state| {
-        state.pop()?.is_eq(state)?;
+    state.add_native_word("not", "(? -- !?)", Some("Logical negation."), |state| {
         state.pop()?.not(state)
     });
 
-    state.add_native_word("<", "(a b -- ?)", |state| state.pop()?.is_lt(state));
-    state.add_native_word(">", "(a b -- ?)", |state| state.pop()?.is_gt(state));
-    state.add_native_word("<=", "(a b -- ?)", |state| state.pop()?.is_le(state));
-    state.add_native_word(">=", "(a b -- ?)", |state| state.pop()?.is_ge(state));
+    state.add_native_word(
+        "==",
+        "(a b -- ?)",
+        Some("Test if a and b are equal."),
+        |state| state.pop()?.is_eq(state),
+    );
 
-    state.add_native_word("+", "( a b -- sum )", |state| state.pop()?.add(state));
-    state.add_native_word("-", "( a b -- diff )", |state| state.pop()?.sub(state));
-    state.add_native_word("*", "( a b -- prod )", |state| state.pop()?.mul(state));
-    state.add_native_word("/", "( a b -- quot )", |state| state.pop()?.div(state));
+    state.add_native_word(
+        "!=",
+        "(a b -- ?)",
+        Some("Test if a and b are not equal."),
+        |state| {
+            state.pop()?.is_eq(state)?;
+            state.pop()?.not(state)
+        },
+    );
+
+    state.add_native_word(
+        "<",
+        "(a b -- ?)",
+        Some("Test if a is less than b."),
+        |state| state.pop()?.is_lt(state),
+    );
+    state.add_native_word(
+        ">",
+        "(a b -- ?)",
+        Some("Test if a is greater than b."),
+        |state| state.pop()?.is_gt(state),
+    );
+    state.add_native_word(
+        "<=",
+        "(a b -- ?)",
+        Some("Test if a is less than or equal to b."),
+        |state| state.pop()?.is_le(state),
+    );
+    state.add_native_word(
+        ">=",
+        "(a b -- ?)",
+        Some("Test if a is greater than or equal to b."),
+        |state| state.pop()?.is_ge(state),
+    );
+
+    state.add_native_word(
+        "+",
+        "( a b -- sum )",
+        Some("Add two numbers, or concatenate two strings."),
+        |state| state.pop()?.add(state),
+    );
+    state.add_native_word(
+        "-",
+        "( a b -- diff )",
+        Some("Subtract b from a."),
+        |state| state.pop()?.sub(state),
+    );
+    state.add_native_word(
+        "*",
+        "( a b -- prod )",
+        Some("Multiply two numbers."),
+        |state| state.pop()?.mul(state),
+    );
+    state.add_native_word("/", "( a b -- quot )", Some("Divide a by b."), |state| {
+        state.pop()?.div(state)
+    });
 
     state.exit_mod().unwrap();
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{stack, tier0};
+
+    #[test]
+    fn capture_output() {
+        let state = &mut State::new();
+        tier0(state).unwrap();
+        stack(state).unwrap();
+        ops(state).unwrap();
+        state.run("USE ops:").unwrap();
+        state.run("USE stack:").unwrap();
+
+        state.assert_run_pop(
+            "1 [ \"x\" emit 65 emit 2 . .s ] with-output-to-string",
+            &["xA\n2\n[1]\n"],
+        );
+        state.assert_stack(&[1]);
+
+        let (capture, buffer) = Output::capture();
+        state.set_output(capture);
+        state
+            .run("\"outer \" emit [ \"inner\" emit ] with-output-to-string drop \"done\" emit")
+            .unwrap();
+        assert_eq!(buffer.contents(), "outer done");
+
+        assert!(state
+            .run("[ \"lost\" emit 1 \"a\" + ] with-output-to-string")
+            .is_err());
+        state.run("\"!\" emit").unwrap();
+        assert_eq!(buffer.contents(), "outer done!");
+    }
+}
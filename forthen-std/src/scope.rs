@@ -1,7 +1,9 @@
 use forthen_core::errors::*;
+use forthen_core::object_factory::StringManager;
 use forthen_core::objects::prelude::*;
 use forthen_core::Object;
 use forthen_core::State;
+use forthen_core::{Entry, IntoStackEffect, Word};
 use std::rc::Rc;
 
 /// Load language tier 0 into the dictionary
@@ -10,95 +12,147 @@ use std::rc::Rc;
 pub fn scope(state: &mut State) -> Result<()> {
     state.new_mod("scope".to_string())?;
 
-    state.add_native_word("push_frame", "(n -- )", |state| {
-        let n = state.pop_i32()? as usize;
-        state.frames.resize(n, Object::None);
-        Ok(())
-    });
-
-    state.add_native_word("pop_frame", "(n -- )", |state| {
-        let n = state.pop_i32()? as usize;
-        state.frames.truncate(state.frames.len() - n);
-        Ok(())
-    });
-
-    state.add_native_word("store", "(x addr -- )", |state| {
-        let addr = state.pop_i32()? as usize;
-        let addr = state.frames.len() - addr - 1;
-        let x = state.pop()?;
-        state.frames[addr] = x.clone();
-        Ok(())
-    });
-
-    state.add_native_word("fetch", "(addr -- x)", |state| {
-        let addr = state.pop_i32()? as usize;
-        let addr = state.frames.len() - addr - 1;
-        let x = state.frames[addr].clone();
-        state.push(x)?;
-        Ok(())
-    });
+    state.add_native_word(
+        "push_frame",
+        "(n -- )",
+        Some("Allocate n local variable slots. Used by words defined with `::`."),
+        |state| {
+            let n = state.pop_i32()? as usize;
+            state.frames.resize(n, Object::None);
+            Ok(())
+        },
+    );
+
+    state.add_native_word(
+        "pop_frame",
+        "(n -- )",
+        Some("Release n local variable slots. Used by words defined with `::`."),
+        |state| {
+            let n = state.pop_i32()? as usize;
+            state.frames.truncate(state.frames.len() - n);
+            Ok(())
+        },
+    );
+
+    state.add_native_word(
+        "store",
+        "(x addr -- )",
+        Some("Store x in the local variable slot addr."),
+        |state| {
+            let addr = state.pop_i32()? as usize;
+            let addr = state.frames.len() - addr - 1;
+            let x = state.pop()?;
+            state.frames[addr] = x.clone();
+            Ok(())
+        },
+    );
+
+    state.add_native_word(
+        "fetch",
+        "(addr -- x)",
+        Some("Push the content of the local variable slot addr."),
+        |state| {
+            let addr = state.pop_i32()? as usize;
+            let addr = state.frames.len() - addr - 1;
+            let x = state.frames[addr].clone();
+            state.push(x)?;
+            Ok(())
+        },
+    );
 
     let push_frame = state.current_module.lookup("push_frame").unwrap().clone();
     let pop_frame = state.current_module.lookup("pop_frame").unwrap().clone();
     let store = state.current_module.lookup("store").unwrap().clone();
     let fetch = state.current_module.lookup("fetch").unwrap().clone();
 
-    state.add_native_parse_word("set", move |state| {
-        let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
-
-        let i = state.scopes.last_mut().unwrap().get_storage_location(&name) as i32;
-
-        let instructions = state.top_mut()?.as_vec_mut()?;
-        instructions.push(i.into());
-        instructions.push(Object::Word(store.clone()));
-        Ok(())
-    });
-
-    state.add_native_parse_word("get", move |state| {
-        let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
-
-        let i = state.scopes.last_mut().unwrap().get_storage_location(&name) as i32;
-
-        let instructions = state.top_mut()?.as_vec_mut()?;
-        instructions.push(i.into());
-        instructions.push(Object::Word(fetch.clone()));
-        Ok(())
-    });
-
-    state.add_native_parse_word("::", move |state| {
-        // todo: parse stack effect from word definition and compare against derived stack effect?
-
-        let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
-
-        let mut se = state.next_token().ok_or(ErrorKind::EndOfInput)?;
-        if se != "(" {
-            return Err(ErrorKind::ExpectedStackEffect.into());
-        }
-        loop {
-            let token = state.next_token().ok_or(ErrorKind::EndOfInput)?;
-            se += " ";
-            se += &token;
-            if token == ")" {
-                break;
+    state.add_native_parse_word(
+        "set",
+        Some(
+            "`set name` pops the top of the stack into the local variable name. Only valid in \
+             `::` definitions.",
+        ),
+        move |state| {
+            let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+
+            let i = state.scopes.last_mut().unwrap().get_storage_location(&name) as i32;
+
+            let instructions = state.top_mut()?.as_vec_mut()?;
+            instructions.push(i.into());
+            instructions.push(Object::Word(store.clone()));
+            Ok(())
+        },
+    );
+
+    state.add_native_parse_word(
+        "get",
+        Some("`get name` pushes the local variable name. Only valid in `::` definitions."),
+        move |state| {
+            let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+
+            let i = state.scopes.last_mut().unwrap().get_storage_location(&name) as i32;
+
+            let instructions = state.top_mut()?.as_vec_mut()?;
+            instructions.push(i.into());
+            instructions.push(Object::Word(fetch.clone()));
+            Ok(())
+        },
+    );
+
+    state.add_native_parse_word(
+        "::",
+        Some(
+            "`:: name ( effect ) ... ;` defines a new word that may use local variables with \
+             `set` and `get`.",
+        ),
+        move |state| {
+            // todo: parse stack effect from word definition and compare against derived stack effect?
+
+            let start = state.last_token_span().cloned();
+            let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+
+            let mut se = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+            if se != "(" {
+                return Err(ErrorKind::ExpectedStackEffect.into());
             }
-        }
-
-        let scope = state.compile_scoped(|state| state.parse_until(";"))?;
-
-        let n_vars = scope.len() as i32;
-
-        let mut quot = Vec::new();
-        quot.push(n_vars.into());
-        quot.push(Object::Word(push_frame.clone()));
-        quot.extend(
-            Rc::try_unwrap(state.pop()?.into_rc_vec()?).or(Err(ErrorKind::OwnershipError))?,
-        );
-        quot.push(n_vars.into());
-        quot.push(Object::Word(pop_frame.clone()));
-
-        state.add_compound_word(name, se, Object::List(Rc::new(quot)));
-        Ok(())
-    });
+            loop {
+                let token = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+                se += " ";
+                se += &token;
+                if token == ")" {
+                    break;
+                }
+            }
+            let comment = state.parse_doc_comment()?;
+
+            let scope = state.compile_scoped(|state| state.parse_until(";"))?;
+
+            let n_vars = scope.len() as i32;
+
+            let mut quot = Vec::new();
+            quot.push(n_vars.into());
+            quot.push(Object::Word(push_frame.clone()));
+            quot.extend(
+                Rc::try_unwrap(state.pop()?.into_rc_vec()?).or(Err(ErrorKind::OwnershipError))?,
+            );
+            quot.push(n_vars.into());
+            quot.push(Object::Word(pop_frame.clone()));
+
+            let name = state.factory.get_string(name);
+            let location = state.span_since(start);
+            let doc = comment.or(state.take_pending_doc());
+            state.add_entry(
+                Entry::new(
+                    name,
+                    Word::Word(Object::List(Rc::new(quot))),
+                    se.into_stack_effect(),
+                )
+                .with_locals(scope.names())
+                .with_location(location)
+                .with_doc(doc),
+            );
+            Ok(())
+        },
+    );
 
     state.exit_mod().unwrap();
 
@@ -6,30 +6,63 @@ pub fn stack(state: &mut State) -> Result<()> {
     state.new_mod("stack".to_string())?;
 
     // development tools
-    state.add_native_word(".s", "( -- )", |state| {
-        println!("{:?}", state.stack);
-        Ok(())
-    });
-    state.add_native_word(".", "( x -- )", |state| {
-        state.pop()?.repr(state)?;
-        println!("{}", state.pop()?.try_into_rc_string()?);
+    state.add_native_word(".s", "( -- )", Some("Print the whole stack."), |state| {
+        writeln!(state.output(), "{:?}", state.stack)?;
         Ok(())
     });
+    state.add_native_word(
+        ".",
+        "( x -- )",
+        Some("Print the top item and remove it from the stack."),
+        |state| {
+            state.pop()?.repr(state)?;
+            let s = state.pop()?.try_into_rc_string()?;
+            writeln!(state.output(), "{}", s)?;
+            Ok(())
+        },
+    );
 
     // stack operations
-    state.add_native_word("dup", "(a -- a a)", State::dup);
-    state.add_native_word("drop", "(a -- )", |state| state.pop().map(|_| ()));
-    state.add_native_word("swap", "(a b -- b a)", State::swap);
-    state.add_native_word("over", "(a b -- a b a)", State::over);
-    state.add_native_word("rot", "(a b c -- b c a)", State::rot);
-    state.add_native_word("2dup", "(a -- a a)", |state| {
-        let b = state.pop()?;
-        let a = state.pop()?;
-        state.push(a.clone())?;
-        state.push(b.clone())?;
-        state.push(a)?;
-        state.push(b)
+    state.add_native_word(
+        "dup",
+        "(a -- a a)",
+        Some("Duplicate the top item."),
+        State::dup,
+    );
+    state.add_native_word("drop", "(a -- )", Some("Remove the top item."), |state| {
+        state.pop().map(|_| ())
     });
+    state.add_native_word(
+        "swap",
+        "(a b -- b a)",
+        Some("Exchange the two top items."),
+        State::swap,
+    );
+    state.add_native_word(
+        "over",
+        "(a b -- a b a)",
+        Some("Copy the second item to the top."),
+        State::over,
+    );
+    state.add_native_word(
+        "rot",
+        "(a b c -- b c a)",
+        Some("Rotate the third item to the top."),
+        State::rot,
+    );
+    state.add_native_word(
+        "2dup",
+        "(a b -- a b a b)",
+        Some("Duplicate the two top items."),
+        |state| {
+            let b = state.pop()?;
+            let a = state.pop()?;
+            state.push(a.clone())?;
+            state.push(b.clone())?;
+            state.push(a)?;
+            state.push(b)
+        },
+    );
 
     state.exit_mod().unwrap();
 
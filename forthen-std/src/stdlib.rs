@@ -1,10 +1,15 @@
 use super::branch::branch;
+use super::check::check;
+use super::input::input;
+use super::introspect::introspect;
 use super::list::list;
 use super::loops::loops;
 use super::ops::ops;
 use super::scope::scope;
 use super::stack::stack;
+use super::sys::sys;
 use super::table::table;
+use super::testing::testing;
 use super::tier0::tier0;
 use super::timeit::timeit;
 use forthen_core::{Result, State};
@@ -16,23 +21,33 @@ pub fn stdlib(state: &mut State) -> Result<()> {
     state.new_mod("std".to_string())?;
 
     branch(state)?;
+    check(state)?;
+    input(state)?;
+    introspect(state)?;
     list(state)?;
     loops(state)?;
     ops(state)?;
     scope(state)?;
     stack(state)?;
+    sys(state)?;
     table(state)?;
+    testing(state)?;
     timeit(state)?;
 
     state.run(
         "
         USE branch:
+        USE check:
+        USE input:
+        USE introspect:
         USE list:
         USE loop:
         USE ops:
         USE scope:
         USE stack:
+        USE sys:
         USE table:
+        USE test:
     ",
     )?;
 
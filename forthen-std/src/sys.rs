@@ -0,0 +1,123 @@
+use forthen_core::errors::*;
+use forthen_core::{Object, State};
+use std::rc::Rc;
+
+/// Load words that interact with the process environment into the dictionary
+pub fn sys(state: &mut State) -> Result<()> {
+    state.new_mod("sys".to_string())?;
+
+    state.add_native_word(
+        "args",
+        "( -- args)",
+        Some("Push the list of arguments passed to the program."),
+        |state| {
+            let args = state
+                .args()
+                .iter()
+                .map(|arg| Object::String(Rc::new(arg.clone())))
+                .collect();
+            state.push(Object::List(Rc::new(args)))
+        },
+    );
+
+    state.add_native_word(
+        "getenv",
+        "(name -- ?value)",
+        Some("Push the value of an environment variable, or None if it is not set."),
+        |state| {
+            let name = state.pop_string()?;
+            match std::env::var(name) {
+                Ok(value) => state.push_string(value),
+                Err(_) => state.push(Object::None),
+            }
+        },
+    );
+
+    state.add_native_word(
+        "setenv",
+        "(name value -- )",
+        Some("Set an environment variable."),
+        |state| {
+            let value = state.pop_string()?;
+            let name = state.pop_string()?;
+            if name.is_empty() || name.contains('=') || name.contains('\0') || value.contains('\0')
+            {
+                return Err(ErrorKind::RuntimeError(format!(
+                    "Invalid environment variable {:?}",
+                    name
+                ))
+                .into());
+            }
+            std::env::set_var(name, value);
+            Ok(())
+        },
+    );
+
+    state.add_native_word(
+        "cwd",
+        "( -- path)",
+        Some("Push the current working directory."),
+        |state| {
+            let cwd = std::env::current_dir()?;
+            state.push_string(cwd.to_string_lossy().into_owned())
+        },
+    );
+
+    state.add_native_word(
+        "exit",
+        "(code -- )",
+        Some("Stop the program with the given exit status."),
+        |state| {
+            let code = state.pop_i32()?;
+            Err(ErrorKind::Exit(code).into())
+        },
+    );
+
+    state.exit_mod().unwrap();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tier0;
+    use forthen_core::objects::prelude::*;
+
+    #[test]
+    fn environment() {
+        let state = &mut State::new();
+        tier0(state).unwrap();
+        sys(state).unwrap();
+        state.run("USE sys:").unwrap();
+
+        state.set_args(vec!["a".to_string(), "-b".to_string()]);
+        state.run("args").unwrap();
+        let args = state.pop().unwrap();
+        assert_eq!(args.as_slice().unwrap(), &["a", "-b"]);
+
+        state
+            .run("\"FORTHEN_SYS_TEST\" \"some value\" setenv")
+            .unwrap();
+        state.assert_run_pop("\"FORTHEN_SYS_TEST\" getenv", &["some value"]);
+        state.assert_run_pop("\"FORTHEN_SYS_TEST_UNSET\" getenv", &[Object::None]);
+        assert!(state.run("\"A=B\" \"x\" setenv").is_err());
+
+        let cwd = std::env::current_dir().unwrap();
+        state.assert_run_pop("cwd", &[cwd.to_str().unwrap()]);
+    }
+
+    #[test]
+    fn exit() {
+        let state = &mut State::new();
+        tier0(state).unwrap();
+        sys(state).unwrap();
+        state.run("USE sys:").unwrap();
+
+        match state.run("1 3 exit 2") {
+            Err(Error(ErrorKind::Exit(3), _)) => {}
+            other => panic!("expected exit, got {:?}", other),
+        }
+        state.assert_stack(&[1]);
+    }
+}
@@ -7,95 +7,138 @@ use forthen_core::State;
 pub fn table(state: &mut State) -> Result<()> {
     state.new_mod("table".to_string())?;
 
-    state.add_native_word("{}", "( -- t)", |state| state.push(Object::new_table()));
-
-    state.add_native_word("set_metatable", "(t mt -- t')", |state| {
-        match state.pop()? {
+    state.add_native_word(
+        "{}",
+        "( -- t)",
+        Some("Create a new empty table."),
+        |state| state.push(Object::new_table()),
+    );
+
+    state.add_native_word(
+        "set_metatable",
+        "(t mt -- t')",
+        Some("Set the meta table of t. A meta table of None removes it."),
+        |state| match state.pop()? {
             Object::Table(mt) => state.top_mut()?.set_meta(Some(mt)),
             Object::None => state.top_mut()?.set_meta(None),
             _ => Err(ErrorKind::TypeError("meta table must be a table".to_string()).into()),
-        }
-    });
-
-    state.add_native_word("get_metatable", "(t -- t mt)", |state| {
-        let mt = state.top_mut()?.get_meta();
-        match mt {
-            Some(mt) => state.push(Object::Table(mt)),
-            None => state.push(Object::None),
-        }
-    });
-
-    state.add_native_parse_word("set_attr", |state| {
-        let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
-        let name = state.factory.get_string(name);
-
-        let set_func = Callable::new_const(move |state| {
-            let value = state.pop()?;
-            state.top_mut()?.set_attr(name.clone(), value);
+        },
+    );
+
+    state.add_native_word(
+        "get_metatable",
+        "(t -- t mt)",
+        Some("Push the meta table of t, or None if it has none."),
+        |state| {
+            let mt = state.top_mut()?.get_meta();
+            match mt {
+                Some(mt) => state.push(Object::Table(mt)),
+                None => state.push(Object::None),
+            }
+        },
+    );
+
+    state.add_native_parse_word(
+        "set_attr",
+        Some(
+            "`set_attr name` compiles code that stores the top item in the attribute name of the \
+             table below.",
+        ),
+        |state| {
+            let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+            let name = state.factory.get_string(name);
+
+            let set_func = Callable::new_const(move |state| {
+                let value = state.pop()?;
+                state.top_mut()?.set_attr(name.clone(), value);
+                Ok(())
+            });
+
+            let instructions = state.top_mut()?.as_vec_mut()?;
+            instructions.push(Object::Function(set_func));
+            Ok(())
+        },
+    );
+
+    state.add_native_parse_word(
+        "get_attr_raw",
+        Some(
+            "`get_attr_raw name` compiles code that pushes the attribute name of the table, \
+             ignoring the meta table.",
+        ),
+        |state| {
+            let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+            let name = state.factory.get_string(name);
+
+            let get_func = Callable::new_const(move |state| {
+                let value = state
+                    .top()?
+                    .get_attr(&name)
+                    .ok_or_else(|| ErrorKind::AttributeError(name.to_string()))?;
+                state.push(value)
+            });
+
+            let instructions = state.top_mut()?.as_vec_mut()?;
+            instructions.push(Object::Function(get_func));
             Ok(())
-        });
-
-        let instructions = state.top_mut()?.as_vec_mut()?;
-        instructions.push(Object::Function(set_func));
-        Ok(())
-    });
-
-    state.add_native_parse_word("get_attr_raw", |state| {
-        let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
-        let name = state.factory.get_string(name);
-
-        let get_func = Callable::new_const(move |state| {
-            let value = state
-                .top()?
-                .get_attr(&name)
-                .ok_or_else(|| ErrorKind::AttributeError(name.to_string()))?;
-            state.push(value)
-        });
-
-        let instructions = state.top_mut()?.as_vec_mut()?;
-        instructions.push(Object::Function(get_func));
-        Ok(())
-    });
-
-    state.add_native_parse_word("get_attr", |state| {
-        let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
-        let name = state.factory.get_string(name);
-
-        let get_func = Callable::new_const(move |state| {
+        },
+    );
+
+    state.add_native_parse_word(
+        "get_attr",
+        Some(
+            "`get_attr name` compiles code that pushes the attribute name of the table, falling \
+             back to the meta table's `__index__`.",
+        ),
+        |state| {
+            let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+            let name = state.factory.get_string(name);
+
+            let get_func = Callable::new_const(move |state| {
+                let this = state.pop()?;
+                state.push(name.clone())?;
+                this.get_attribute(state)?;
+                state.push(this)?;
+                state.swap()
+            });
+
+            let instructions = state.top_mut()?.as_vec_mut()?;
+            instructions.push(Object::Function(get_func));
+            Ok(())
+        },
+    );
+
+    state.add_native_word(
+        "get_attribute",
+        "(t s -- t v)",
+        Some("Push the attribute of t named by the string s."),
+        |state| {
+            let attr = state.pop()?;
             let this = state.pop()?;
-            state.push(name.clone())?;
+            state.push(attr)?;
             this.get_attribute(state)?;
             state.push(this)?;
             state.swap()
-        });
-
-        let instructions = state.top_mut()?.as_vec_mut()?;
-        instructions.push(Object::Function(get_func));
-        Ok(())
-    });
-
-    state.add_native_word("get_attribute", "(t s -- t v)", |state| {
-        let attr = state.pop()?;
-        let this = state.pop()?;
-        state.push(attr)?;
-        this.get_attribute(state)?;
-        state.push(this)?;
-        state.swap()
-    });
-
-    state.add_native_parse_word("has_attr", |state| {
-        let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
-        let name = state.factory.get_string(name);
-
-        let get_func = Callable::new_const(move |state| match state.top()?.get_attr(&name) {
-            Some(_) => state.push(Object::True),
-            None => state.push(Object::False),
-        });
-
-        let instructions = state.top_mut()?.as_vec_mut()?;
-        instructions.push(Object::Function(get_func));
-        Ok(())
-    });
+        },
+    );
+
+    state.add_native_parse_word(
+        "has_attr",
+        Some("`has_attr name` compiles code that tests if the table has the attribute name."),
+        |state| {
+            let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+            let name = state.factory.get_string(name);
+
+            let get_func = Callable::new_const(move |state| match state.top()?.get_attr(&name) {
+                Some(_) => state.push(Object::True),
+                None => state.push(Object::False),
+            });
+
+            let instructions = state.top_mut()?.as_vec_mut()?;
+            instructions.push(Object::Function(get_func));
+            Ok(())
+        },
+    );
 
     state.exit_mod().unwrap();
 
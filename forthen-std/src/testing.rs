@@ -0,0 +1,308 @@
+use forthen_core::errors::*;
+use forthen_core::objects::prelude::*;
+use forthen_core::{Entry, IntoStackEffect, Mode, Object, State, Word, WordId};
+use std::rc::Rc;
+
+/// Name of the module, under the root, that `TEST:` adds tests to
+const TESTS_MODULE: &str = "tests";
+
+/// Load the unit testing vocabulary into the dictionary
+pub fn testing(state: &mut State) -> Result<()> {
+    state.new_mod("test".to_string())?;
+
+    state.add_native_parse_word(
+        "TEST:",
+        Some(
+            "`TEST: name ... ;` defines a unit test. Tests are collected in the `:tests:` module \
+             and run by `forthen test`.",
+        ),
+        |state| {
+            let start = state.last_token_span().cloned();
+            let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+            let comment = state.parse_doc_comment()?;
+
+            state.compile(|state| state.parse_until(";"))?;
+
+            let body = state.pop()?;
+            let name = Rc::new(name);
+            let location = state.span_since(start);
+            let doc = comment.or(state.take_pending_doc());
+
+            let entry = Entry::new(name, Word::Word(body), "( -- )".into_stack_effect())
+                .with_location(location)
+                .with_doc(doc);
+            state.with_module(TESTS_MODULE, |state| {
+                if state.current_module.local_lookup(&entry.name).is_some() {
+                    return Err(ErrorKind::RuntimeError(format!(
+                        "test {} is defined twice",
+                        entry.name
+                    ))
+                    .into());
+                }
+                state.add_entry(entry);
+                Ok(())
+            })
+        },
+    );
+
+    state.add_native_word(
+        "assert",
+        "(? -- )",
+        Some("Fail unless the condition is true."),
+        |state| {
+            if state.pop()?.try_into_bool()? {
+                Ok(())
+            } else {
+                Err(ErrorKind::AssertionError("condition is false".to_string()).into())
+            }
+        },
+    );
+
+    state.add_native_word(
+        "assert=",
+        "(actual expected -- )",
+        Some("Fail unless the two values are equal."),
+        |state| {
+            let expected = state.pop()?;
+            let actual = state.pop()?;
+            if equal(state, &actual, &expected)? {
+                Ok(())
+            } else {
+                Err(ErrorKind::AssertionError(format!(
+                    "expected {} but got {}",
+                    expected.repr_sys(),
+                    actual.repr_sys()
+                ))
+                .into())
+            }
+        },
+    );
+
+    state.add_native_word(
+        "expect-error",
+        "(..a quot -- ..a)",
+        Some(
+            "Fail unless calling the quotation raises an error. The stack is restored afterwards. \
+             Used by `assert-error`.",
+        ),
+        |state| {
+            let quot = state.pop()?;
+            let stack = state.stack.clone();
+            let result = quot.call(state);
+            state.stack = stack;
+            match result {
+                // exiting and failed assertions are not the error the test expects
+                Err(e) if matches!(e.kind(), ErrorKind::Exit(_) | ErrorKind::AssertionError(_)) => {
+                    Err(e)
+                }
+                Err(_) => Ok(()),
+                Ok(()) => Err(ErrorKind::AssertionError(format!(
+                    "expected an error from {}",
+                    quot.repr_sys()
+                ))
+                .into()),
+            }
+        },
+    );
+
+    state.add_native_word(
+        "expect-stack",
+        "(..a quot -- ..a)",
+        Some(
+            "Fail unless the stack equals the values pushed by calling the quotation on an empty \
+             stack. Used by `assert-stack{`.",
+        ),
+        |state| {
+            let quot = state.pop()?;
+            let sub = &mut state.substate();
+            quot.call(sub)?;
+            let expected = std::mem::take(&mut sub.stack);
+
+            let mut same = expected.len() == state.stack.len();
+            for (a, b) in state.stack.clone().iter().zip(&expected) {
+                same = same && equal(state, a, b)?;
+            }
+            if same {
+                Ok(())
+            } else {
+                Err(ErrorKind::AssertionError(format!(
+                    "expected stack {} but found {}",
+                    stack_text(&expected),
+                    stack_text(&state.stack)
+                ))
+                .into())
+            }
+        },
+    );
+
+    let expect_error = state.lookup("expect-error")?.unwrap();
+    state.add_native_parse_word(
+        "assert-error",
+        Some("`assert-error [ ... ]` fails unless the quotation raises an error."),
+        move |state| {
+            match state.next_token() {
+                Some(ref token) if token == "[" => state.parse_token(token)?,
+                _ => {
+                    return Err(ErrorKind::TypeError(
+                        "assert-error expects a quotation".to_string(),
+                    )
+                    .into())
+                }
+            }
+            call_or_compile(state, &expect_error)
+        },
+    );
+
+    let expect_stack = state.lookup("expect-stack")?.unwrap();
+    state.add_native_parse_word(
+        "assert-stack{",
+        Some(
+            "`assert-stack{ 1 2 3 }` fails unless the stack holds exactly the values between the \
+             braces.",
+        ),
+        move |state| {
+            state.compile(|state| state.parse_until("}"))?;
+            if let Mode::Compile = state.current_mode() {
+                let code = state.pop()?;
+                state.compile_object(code)?;
+            }
+            call_or_compile(state, &expect_stack)
+        },
+    );
+
+    state.exit_mod().unwrap();
+
+    Ok(())
+}
+
+/// The tests defined with `TEST:`, in the order they appear in the source.
+pub fn collect_tests(state: &State) -> Vec<WordId> {
+    let module = match state.root_module().access_path(TESTS_MODULE) {
+        Some(module) => module,
+        None => return vec![],
+    };
+    let mut tests: Vec<_> = module
+        .local_keys()
+        .iter()
+        .filter_map(|name| module.local_lookup(name))
+        .collect();
+    tests.sort_by_key(|test| {
+        test.location
+            .as_ref()
+            .map(|span| (span.source.name.clone(), span.start))
+    });
+    tests
+}
+
+/// Call the word right away, or compile a call if a definition is being compiled.
+fn call_or_compile(state: &mut State, word: &WordId) -> Result<()> {
+    match state.current_mode() {
+        Mode::Eval => Object::Word(word.clone()).call(state),
+        Mode::Compile => state.compile_word(word.clone()),
+    }
+}
+
+/// Structural equality; lists are compared item by item and tables with `==`.
+fn equal(state: &mut State, a: &Object, b: &Object) -> Result<bool> {
+    use Object::*;
+    match (a, b) {
+        (List(a), List(b)) => {
+            if a.len() != b.len() {
+                return Ok(false);
+            }
+            for (x, y) in a.iter().zip(b.iter()) {
+                if !equal(state, x, y)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        (Table(_), _) | (Extension(_), _) => {
+            state.push(b.clone())?;
+            a.is_eq(state)?;
+            state.pop_bool()
+        }
+        (None, None) | (True, True) | (False, False) => Ok(true),
+        (I32(a), I32(b)) => Ok(a == b),
+        (String(a), String(b)) => Ok(a == b),
+        (Word(a), Word(b)) => Ok(Rc::ptr_eq(a, b)),
+        (Function(a), Function(b)) => Ok(a == b),
+        _ => Ok(false),
+    }
+}
+
+fn stack_text(stack: &[Object]) -> String {
+    let items: Vec<_> = stack.iter().map(|obj| obj.repr_sys()).collect();
+    format!("[{}]", items.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stdlib;
+
+    #[test]
+    fn assertions() {
+        let state = &mut State::new();
+        stdlib(state).unwrap();
+        state.run("USE std:").unwrap();
+
+        state.run("True assert 1 1 assert=").unwrap();
+        state.run("[ 1 \"a\" ] [ 1 \"a\" ] assert=").unwrap();
+        assert!(state.run("False assert").is_err());
+        assert!(state.run("1 \"1\" assert=").is_err());
+
+        state.run("7 assert-error [ drop drop ]").unwrap();
+        state.assert_stack(&[7]);
+        assert!(state.run("assert-error [ 1 ]").is_err());
+        assert!(state.run("assert-error [ 0 exit ]").is_err());
+        assert!(state.run("assert-error [ False assert ]").is_err());
+        assert!(state.run("assert-error 1").is_err());
+
+        state.run("assert-stack{ 3 4 + }").unwrap();
+        let err = state.run("assert-stack{ 7 8 }").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Assertion Failed: expected stack [7, 8] but found [7]"
+        );
+    }
+
+    #[test]
+    fn test_definitions() {
+        let state = &mut State::new();
+        stdlib(state).unwrap();
+        state.run("USE std:").unwrap();
+        assert!(collect_tests(state).is_empty());
+
+        state
+            .run(
+                "
+            : square ( x -- y ) dup * ;
+            TEST: squares 3 square 9 assert= ;
+            TEST: fails assert-stack{ 1 } ;
+            TEST: errors assert-error [ \"x\" square ] ;
+            ",
+            )
+            .unwrap();
+        assert!(state.lookup("squares").unwrap().is_none());
+        let err = state.run("TEST: squares ;").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Runtime Error: test squares is defined twice"
+        );
+
+        let tests = collect_tests(state);
+        let names: Vec<_> = tests.iter().map(|test| test.name.as_str()).collect();
+        assert_eq!(names, vec!["squares", "fails", "errors"]);
+
+        let results: Vec<_> = tests
+            .iter()
+            .map(|test| {
+                Object::Word(test.clone())
+                    .call(&mut state.substate())
+                    .is_ok()
+            })
+            .collect();
+        assert_eq!(results, vec![true, false, true]);
+    }
+}
@@ -1,190 +1,349 @@
 use forthen_core::errors::*;
 use forthen_core::object_factory::StringManager;
+use forthen_core::objects::callable::Callable;
 use forthen_core::objects::prelude::*;
+use forthen_core::{Entry, IntoStackEffect, Source, StackEffect, Word};
 use forthen_core::{State, Mode};
 
 /// Load language tier 0 into the dictionary
 ///
 /// Tier 0 contains low level native words required for extending the language
 pub fn tier0(state: &mut State) -> Result<()> {
-    state.add_native_parse_word(";", |_| Err(ErrorKind::UnexpectedDelimiter(";").into()));
-    state.add_native_parse_word("]", |_| Err(ErrorKind::UnexpectedDelimiter("]").into()));
-
-    state.add_native_word("None", "( -- none)", |state| state.push(Object::None));
-    state.add_native_word("True", "( -- none)", |state| state.push(Object::True));
-    state.add_native_word("False", "( -- none)", |state| state.push(Object::False));
-
-    state.add_native_word("error", "(msg -- )", |state| {
-        let msg = state.pop_string()?;
-        Err(ErrorKind::RuntimeError(msg).into())
-    });
-
-    state.add_native_word("next_token", "( -- token)", |state| {
-        let token = state.next_token().ok_or(ErrorKind::EndOfInput)?;
-        let token = state.factory.get_string(token);
-        state.push(token)?;
-        Ok(())
-    });
-
-    state.add_native_parse_word("MODULE", |state| {
-        let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
-        state.new_mod(name)
+    state.add_native_parse_word(
+        ";",
+        Some("Terminate a definition. Using it anywhere else is an error."),
+        |_| Err(ErrorKind::UnexpectedDelimiter(";").into()),
+    );
+    state.add_native_parse_word(
+        "]",
+        Some("Terminate a quotation started with `[`. Using it anywhere else is an error."),
+        |_| Err(ErrorKind::UnexpectedDelimiter("]").into()),
+    );
+
+    state.add_native_word(
+        "None",
+        "( -- none)",
+        Some("Push the None object."),
+        |state| state.push(Object::None),
+    );
+    state.add_native_word("True", "( -- none)", Some("Push boolean true."), |state| {
+        state.push(Object::True)
     });
-
-    state.add_native_parse_word("USE", |state| {
-        let fullpath = state.next_token().ok_or(ErrorKind::EndOfInput)?;
-
-        let mut split = fullpath.rsplitn(2, ':');
-        let word = split.next().ok_or(ErrorKind::PathError)?;
-        let mut path = split.next().ok_or(ErrorKind::PathError)?;
-
-        let relative = if path.starts_with(':') {
-            path = &path[1..];
-            state.root_module()
-        } else {
-            &state.current_module
-        };
-
-        let target_mod = relative.access_path(path).ok_or(ErrorKind::PathError)?;
-
-        if word != "" {
-            let word_id = target_mod
-                .lookup(word)
-                .ok_or_else(|| ErrorKind::UnknownWord(fullpath))?;
-
-            state
-                .current_module
-                .insert_ref(word_id.name.clone(), word_id);
-        } else {
-            for name in target_mod.local_keys() {
+    state.add_native_word(
+        "False",
+        "( -- none)",
+        Some("Push boolean false."),
+        |state| state.push(Object::False),
+    );
+
+    state.add_native_word(
+        "error",
+        "(msg -- )",
+        Some("Raise a runtime error with the given message."),
+        |state| {
+            let msg = state.pop_string()?;
+            Err(ErrorKind::RuntimeError(msg).into())
+        },
+    );
+
+    state.add_native_word(
+        "next_token",
+        "( -- token)",
+        Some("Read the next token from the input and push it as a string."),
+        |state| {
+            let token = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+            let token = state.factory.get_string(token);
+            state.push(token)?;
+            Ok(())
+        },
+    );
+
+    state.add_native_parse_word(
+        "MODULE",
+        Some("`MODULE name` starts the definition of a new submodule of the current module."),
+        |state| {
+            let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+            state.new_mod(name)
+        },
+    );
+
+    state.add_native_parse_word(
+        "INCLUDE",
+        Some(
+            "`INCLUDE file` runs a source file, searched for next to the including file, as given \
+             and then in the include paths.",
+        ),
+        |state| {
+            let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+            state.include(&name)
+        },
+    );
+
+    state.add_native_parse_word(
+        "USE",
+        Some(
+            "`USE path:word` imports a word into the current module; `USE path:` imports all \
+             words of a module. Paths starting with `:` are absolute.",
+        ),
+        |state| {
+            let fullpath = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+
+            let mut split = fullpath.rsplitn(2, ':');
+            let word = split.next().ok_or(ErrorKind::PathError)?;
+            let path = split.next().ok_or(ErrorKind::PathError)?;
+
+            let target_mod = state.lookup_module(if path.is_empty() { ":" } else { path })?;
+
+            if word != "" {
                 let word_id = target_mod
-                    .lookup(&*name)
-                    .ok_or_else(|| ErrorKind::UnknownWord(fullpath.clone()))?;
+                    .lookup(word)
+                    .ok_or_else(|| ErrorKind::UnknownWord(fullpath))?;
+                state.check_word(&word_id)?;
 
                 state
                     .current_module
                     .insert_ref(word_id.name.clone(), word_id);
+            } else {
+                for name in target_mod.local_keys() {
+                    let word_id = target_mod
+                        .lookup(&*name)
+                        .ok_or_else(|| ErrorKind::UnknownWord(fullpath.clone()))?;
+
+                    // importing a whole module silently skips words the sandbox does not allow
+                    if state.check_word(&word_id).is_ok() {
+                        state
+                            .current_module
+                            .insert_ref(word_id.name.clone(), word_id);
+                    }
+                }
             }
-        }
-
-        Ok(())
-    });
-
-    state.add_native_parse_word("SYNTAX:", |state| {
-        let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
-
-        state.compile(|state| state.parse_until(";"))?;
-
-        let obj = state.pop()?;
-        state.add_compound_parse_word(name, obj);
-        Ok(())
-    });
-
-    state.add_native_parse_word("LET:", |state| {
-        let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
 
-        state.compile(|state| state.parse_until(";"))?;
-
-        let obj = state.pop()?;
-        obj.call(state)?;
-
-        let value = state.pop()?;
-
-        state.add_native_word(name, "( -- x)", move |state| state.push(value.clone()));
-        Ok(())
-    });
+            Ok(())
+        },
+    );
+
+    state.add_native_parse_word(
+        "SYNTAX:",
+        Some("`SYNTAX: name ... ;` defines a parsing word, which is executed at parse time."),
+        |state| {
+            let start = state.last_token_span().cloned();
+            let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+            let comment = state.parse_doc_comment()?;
+
+            state.compile(|state| state.parse_until(";"))?;
+
+            let obj = state.pop()?;
+            let name = state.factory.get_string(name);
+            let location = state.span_since(start);
+            let doc = comment.or(state.take_pending_doc());
+            state.add_entry(
+                Entry::new(name, Word::ParsingWord(obj), StackEffect::new_mod("acc"))
+                    .with_location(location)
+                    .with_doc(doc),
+            );
+            Ok(())
+        },
+    );
+
+    state.add_native_parse_word(
+        "LET:",
+        Some(
+            "`LET: name ... ;` evaluates the body once and defines a word that pushes the result.",
+        ),
+        |state| {
+            let start = state.last_token_span().cloned();
+            let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+            let comment = state.parse_doc_comment()?;
+
+            state.compile(|state| state.parse_until(";"))?;
+
+            let obj = state.pop()?;
+            obj.call(state)?;
+
+            let value = state.pop()?;
+
+            let func = Callable::new_const(move |state| state.push(value.clone()));
+            let name = state.factory.get_string(name);
+            let location = state.span_since(start);
+            let doc = comment.or(state.take_pending_doc());
+            state.add_entry(
+                Entry::new(
+                    name,
+                    Word::Word(Object::Function(func)),
+                    "( -- x)".into_stack_effect(),
+                )
+                .with_location(location)
+                .with_doc(doc),
+            );
+            Ok(())
+        },
+    );
 
-    state.add_native_parse_word(":", |state| {
-        // todo: parse stack effect from word definition and compare against derived stack effect?
+    state.add_native_parse_word(
+        ":",
+        Some("`: name ( effect ) ... ;` defines a new word."),
+        |state| {
+            // todo: parse stack effect from word definition and compare against derived stack effect?
 
-        let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+            let start = state.last_token_span().cloned();
+            let name = state.next_token().ok_or(ErrorKind::EndOfInput)?;
 
-        let mut se = state.next_token().ok_or(ErrorKind::EndOfInput)?;
-        if se != "(" {
-            return Err(ErrorKind::ExpectedStackEffect.into());
-        }
-        loop {
-            let token = state.next_token().ok_or(ErrorKind::EndOfInput)?;
-            se += " ";
-            se += &token;
-            if token == ")" {
-                break;
+            let mut se = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+            if se != "(" {
+                return Err(ErrorKind::ExpectedStackEffect.into());
             }
-        }
-
-        state.compile(|state| state.parse_until(";"))?;
-
-        let quot = state.pop()?;
-        state.add_compound_word(name, se, quot);
-        Ok(())
-    });
-
-    state.add_native_parse_word("[", |state| {
-        state.compile(|state| state.parse_until("]"))?;
-
-        match state.current_mode() {
-            Mode::Eval => {}
-            Mode::Compile => {
-                let code = state.pop()?;
-                state.compile_object(code)?;
+            loop {
+                let token = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+                se += " ";
+                se += &token;
+                if token == ")" {
+                    break;
+                }
             }
-        }
-
-        Ok(())
-    });
-
-    state.add_native_parse_word("(", |state| {
-        let mut nesting = 1;
-        while nesting > 0 {
-            match state.next_token().as_ref().map(String::as_str) {
-                None => return Err(ErrorKind::EndOfInput.into()),
-                Some("(") => nesting += 1,
-                Some(")") => nesting -= 1,
-                Some(_) => {}
+            let comment = state.parse_doc_comment()?;
+
+            state.compile(|state| state.parse_until(";"))?;
+
+            let quot = state.pop()?;
+            let name = state.factory.get_string(name);
+            let location = state.span_since(start);
+            let doc = comment.or(state.take_pending_doc());
+            state.add_entry(
+                Entry::new(name, Word::Word(quot), se.into_stack_effect())
+                    .with_location(location)
+                    .with_doc(doc),
+            );
+            Ok(())
+        },
+    );
+
+    state.add_native_parse_word(
+        "[",
+        Some("`[ ... ]` creates a quotation, i.e. a list of code that can be called later."),
+        |state| {
+            let start = state.last_token_span().cloned();
+            state.compile(|state| state.parse_until("]"))?;
+
+            if let (Some(coverage), Some(span)) = (state.coverage(), state.span_since(start)) {
+                coverage.add_quotation(state.stack.last().unwrap(), span);
             }
-        }
-        Ok(())
-    });
 
-    state.add_native_word("call", "(..a func(..a -- ..b) -- ..b)", |state| {
-        let func = state.pop()?;
-        func.call(state)
-    });
-
-    state.add_native_word("parse", "(str -- ?obj)", |state| {
-        let token = state.pop_string()?;
-        match state.factory.parse(&token) {
-            Some(obj) => state.push(obj),
-            None => state.push(Object::None),
-        }
-    });
-
-    state.add_native_word("lookup", "(str -- ?word)", |state| {
-        let token = state.pop_string()?;
-        match state.current_module.lookup(&token) {
-            Some(entry) => state.push(Object::Word(entry)),
-            None => state.push(Object::None),
-        }
-    });
+            match state.current_mode() {
+                Mode::Eval => {}
+                Mode::Compile => {
+                    let code = state.pop()?;
+                    state.compile_object(code)?;
+                }
+            }
 
-    state.add_native_word("bake", "(list obj -- list')", |state| {
-        match state.current_mode() {
+            Ok(())
+        },
+    );
+
+    state.add_native_parse_word(
+        "(",
+        Some("`( ... )` is a comment. Comments may be nested."),
+        |state| {
+            let mut nesting = 1;
+            while nesting > 0 {
+                match state.next_token().as_ref().map(String::as_str) {
+                    None => return Err(ErrorKind::EndOfInput.into()),
+                    Some("(") => nesting += 1,
+                    Some(")") => nesting -= 1,
+                    Some(_) => {}
+                }
+            }
+            Ok(())
+        },
+    );
+
+    state.add_native_word(
+        "call",
+        "(..a func(..a -- ..b) -- ..b)",
+        Some("Call a quotation or word."),
+        |state| {
+            let func = state.pop()?;
+            if let Object::Word(ref entry) = func {
+                state.check_word(entry)?;
+            }
+            func.call(state)
+        },
+    );
+
+    state.add_native_word(
+        "eval",
+        "(..a code -- ..b)",
+        Some("Parse and run a string of code."),
+        |state| {
+            let code = state.pop_string()?;
+            state.run_source(Source::new("<eval>", code))
+        },
+    );
+
+    state.add_native_word(
+        "parse",
+        "(str -- ?obj)",
+        Some("Parse a string as literal. Pushes None if the string is not a literal."),
+        |state| {
+            let token = state.pop_string()?;
+            match state.factory.parse(&token) {
+                Some(obj) => state.push(obj),
+                None => state.push(Object::None),
+            }
+        },
+    );
+
+    state.add_native_word(
+        "lookup",
+        "(str -- ?word)",
+        Some("Look up a word by name in the current module. Pushes None if there is no such word."),
+        |state| {
+            let token = state.pop_string()?;
+            match state.lookup(&token)? {
+                Some(entry) => state.push(Object::Word(entry)),
+                None => state.push(Object::None),
+            }
+        },
+    );
+
+    state.add_native_word(
+        "bake",
+        "(list obj -- list')",
+        Some("Compile an object into the definition that is currently being compiled."),
+        |state| match state.current_mode() {
             Mode::Eval => Ok(()),
             Mode::Compile => {
                 let code = state.pop()?;
                 state.compile_object(code)
             }
-        }
-    });
-
-    state.add_native_parse_word("DELAY", |state| {
-        let token = state.next_token().ok_or(ErrorKind::EndOfInput)?;
-        let word = state
-            .current_module
-            .lookup(&token)
-            .ok_or_else(|| ErrorKind::UnknownWord(token))?;
-        state.compile_word(word)
-    });
+        },
+    );
+
+    state.add_native_parse_word(
+        "DOC",
+        Some("Use the preceding string literal as doc string of the word being defined."),
+        |state| {
+            let doc = state
+                .top_mut()?
+                .as_vec_mut()?
+                .pop()
+                .ok_or(ErrorKind::StackUnderflow)?;
+            let doc = doc.try_into_rc_string()?;
+            state.set_pending_doc(doc.to_string())
+        },
+    );
+
+    state.add_native_parse_word(
+        "DELAY",
+        Some("`DELAY word` compiles a call to a parsing word instead of executing it."),
+        |state| {
+            let token = state.next_token().ok_or(ErrorKind::EndOfInput)?;
+            let word = state
+                .lookup(&token)?
+                .ok_or_else(|| ErrorKind::UnknownWord(token))?;
+            state.compile_word(word)
+        },
+    );
 
     Ok(())
 }
@@ -217,14 +376,14 @@ mod tests {
         let state = &mut State::new();
         tier0(state).unwrap();
 
-        state.add_native_word("swap", "(a b -- b a)", |state| {
+        state.add_native_word("swap", "(a b -- b a)", None, |state| {
             let b = state.pop()?;
             let a = state.pop()?;
             state.push(b)?;
             state.push(a)?;
             Ok(())
         });
-        state.add_native_word(".s", "( -- )", |state| {
+        state.add_native_word(".s", "( -- )", None, |state| {
             println!("{:?}", state.stack);
             Ok(())
         });
@@ -342,4 +501,76 @@ mod tests {
 
         state.assert_pop(123);
     }
+
+    #[test]
+    fn nested_input() {
+        let state = &mut State::new();
+        tier0(state).unwrap();
+
+        state.add_native_word("nested", "( -- a b)", None, |state| state.run("1 2"));
+        state.add_native_word("try-unknown", "( -- ?)", None, |state| {
+            let failed = state.run("3 no-such-word 4").is_err();
+            state.push(failed)
+        });
+
+        state.run("nested 3").unwrap();
+        state.assert_stack(&[1, 2, 3]);
+        state.stack.clear();
+
+        // the failing inner run must not discard the outer input
+        state.run("try-unknown 5").unwrap();
+        state.assert_pop(5);
+        assert!(state.pop_bool().unwrap());
+        state.assert_pop(3);
+
+        state.assert_run_pop("\"1 2\" eval 3", &[1, 2, 3]);
+        state.run(": twice ( -- x x ) \"21 21\" eval ;").unwrap();
+        state.assert_run_pop("twice", &[21, 21]);
+        state.assert_run_pop("\"twice nested\" eval", &[21, 21, 1, 2]);
+
+        assert!(state.run("\"[ 1\" eval 2").is_err());
+        state.stack.clear();
+        state.assert_run_pop("7", &[7]);
+    }
+
+    #[test]
+    fn include_files() {
+        let dir = std::env::temp_dir().join(format!("forthen-include-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("lib")).unwrap();
+        std::fs::write(dir.join("lib").join("answer.fn"), ": answer ( -- x ) 42 ;").unwrap();
+
+        let state = &mut State::new();
+        tier0(state).unwrap();
+
+        assert!(state.run("INCLUDE answer.fn").is_err());
+
+        state.add_include_path(dir.join("lib"));
+        state.run("INCLUDE answer.fn").unwrap();
+        state.assert_run_pop("answer", &[42]);
+
+        let entry = state.current_module.lookup("answer").unwrap();
+        assert!(entry
+            .location
+            .as_ref()
+            .unwrap()
+            .source
+            .name
+            .ends_with("answer.fn"));
+
+        // relative to the including file first, then the include paths
+        std::fs::write(dir.join("lib").join("main.fn"), "INCLUDE helper.fn").unwrap();
+        std::fs::write(dir.join("lib").join("helper.fn"), ": helper ( -- x ) 1 ;").unwrap();
+        std::fs::write(dir.join("helper.fn"), ": helper ( -- x ) 2 ;").unwrap();
+        state.add_include_path(&dir);
+        state.run("INCLUDE lib/main.fn").unwrap();
+        state.assert_run_pop("helper", &[1]);
+
+        std::fs::write(dir.join("bad.fn"), [0xff, 0xfe]).unwrap();
+        match state.run("INCLUDE bad.fn") {
+            Err(Error(ErrorKind::IoError(path, _), _)) => assert!(path.ends_with("bad.fn")),
+            other => panic!("expected an IoError, got {:?}", other),
+        }
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
 }
@@ -1,75 +1,421 @@
+use crate::decompile::decompile_object;
 use forthen_core::errors::*;
 use forthen_core::objects::prelude::*;
-use forthen_core::State;
+use forthen_core::{Object, Profiler, State};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
-use std::time::Instant;
+/// Time budget of `timeit` and `compare`
+const DEFAULT_BUDGET: Duration = Duration::from_millis(100);
 
-/// Load basic operations into the dictionary
+/// Runs needed to estimate the standard deviation
+const MIN_RUNS: usize = 3;
+
+/// Statistics of the run times of a quotation, in nanoseconds
+#[derive(Debug, Clone, PartialEq)]
+struct Stats {
+    runs: usize,
+    mean: f64,
+    std: f64,
+    min: f64,
+    median: f64,
+    p95: f64,
+}
+
+impl Stats {
+    fn from_times(mut times: Vec<f64>) -> Self {
+        times.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let n = times.len();
+        let mean = times.iter().sum::<f64>() / n as f64;
+        let var = times.iter().map(|t| (t - mean) * (t - mean)).sum::<f64>() / (n as f64 - 1.0);
+        let median = if n % 2 == 1 {
+            times[n / 2]
+        } else {
+            (times[n / 2 - 1] + times[n / 2]) / 2.0
+        };
+        // nearest rank
+        let p95 = times[((n as f64 * 0.95).ceil() as usize).max(1) - 1];
+
+        Stats {
+            runs: n,
+            mean,
+            std: var.sqrt(),
+            min: times[0],
+            median,
+            p95,
+        }
+    }
+
+    const FIELDS: [&'static str; 6] = ["runs", "mean", "std", "min", "median", "p95"];
+
+    /// Units of times in tables and printed statistics, with their length in nanoseconds
+    const UNITS: [(&'static str, f64); 4] = [("ns", 1.0), ("µs", 1e3), ("ms", 1e6), ("s", 1e9)];
+
+    /// A table with one attribute per field and the attribute `unit`. Times are given in the
+    /// finest unit in which all of them fit into an i32.
+    fn to_table(&self) -> Result<Object> {
+        let times = [self.mean, self.std, self.min, self.median, self.p95];
+        let longest = times.iter().cloned().fold(0.0, f64::max);
+        let (unit, scale) = Self::UNITS
+            .iter()
+            .find(|(_, scale)| (longest / scale).round() <= i32::MAX as f64)
+            .ok_or_else(|| ErrorKind::RuntimeError("run time too long to report".to_string()))?;
+
+        let mut table = Object::new_table();
+        table.set_attr(Rc::new("runs".to_string()), Object::I32(self.runs as i32));
+        for (name, time) in Self::FIELDS[1..].iter().zip(times.iter()) {
+            let value = (time / scale).round() as i32;
+            table.set_attr(Rc::new(name.to_string()), Object::I32(value));
+        }
+        table.set_attr(
+            Rc::new("unit".to_string()),
+            Object::String(Rc::new(unit.to_string())),
+        );
+        Ok(table)
+    }
+
+    fn from_table(table: &Object) -> Result<Self> {
+        let attr = |name: &str| {
+            table
+                .get_attr(name)
+                .ok_or_else(|| Error::from(ErrorKind::AttributeError(name.to_string())))
+        };
+        let unit = attr("unit")?.try_into_rc_string()?;
+        let scale = Self::UNITS
+            .iter()
+            .find(|(name, _)| **name == **unit)
+            .map(|(_, scale)| *scale)
+            .ok_or_else(|| ErrorKind::RuntimeError(format!("unknown time unit {}", unit)))?;
+        let get = |name: &str| -> Result<f64> { Ok(attr(name)?.try_into_i32()? as f64 * scale) };
+        Ok(Stats {
+            runs: attr("runs")?.try_into_i32()? as usize,
+            mean: get("mean")?,
+            std: get("std")?,
+            min: get("min")?,
+            median: get("median")?,
+            p95: get("p95")?,
+        })
+    }
+}
+
+impl std::fmt::Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} runs, mean {} (± {} std), min {}, median {}, p95 {}",
+            self.runs,
+            format_time(self.mean),
+            format_time(self.std),
+            format_time(self.min),
+            format_time(self.median),
+            format_time(self.p95)
+        )
+    }
+}
+
+/// Call setup and then run in a fresh substate, until the budget is used up. The first `warmup`
+/// runs are not measured.
+fn measure(
+    state: &State,
+    setup: &Object,
+    runner: &Object,
+    budget: Duration,
+    warmup: usize,
+) -> Result<Stats> {
+    for _ in 0..warmup {
+        let sub = &mut state.substate();
+        setup.call(sub)?;
+        runner.call(sub)?;
+    }
+
+    let mut times = vec![];
+    let total = Instant::now();
+    while total.elapsed() < budget || times.len() < MIN_RUNS {
+        let sub = &mut state.substate();
+        setup.call(sub)?;
+
+        let start = Instant::now();
+        runner.call(sub)?;
+        times.push(start.elapsed().as_nanos() as f64);
+    }
+
+    Ok(Stats::from_times(times))
+}
+
+/// Load benchmarking and profiling words into the dictionary
 pub fn timeit(state: &mut State) -> Result<()> {
     state.new_mod("timeit".to_string())?;
 
     state.add_native_word(
         "timeit",
-        "(..a setup( -- ..b) run(..c -- ..d) -- ..a)",
+        "(..a setup( -- ..b) run(..b -- ..c) -- ..a stats)",
+        Some(
+            "Repeatedly call setup and then run for 100 ms, and push a table of statistics about \
+             the time spent in run: `runs`, `mean`, `std`, `min`, `median` and `p95`. The times \
+             are given in `unit`, one of ns, µs, ms and s.",
+        ),
         |state| {
             let runner = state.pop()?;
             let setup = state.pop()?;
+            let stats = measure(state, &setup, &runner, DEFAULT_BUDGET, 0)?;
+            state.push(stats.to_table()?)
+        },
+    );
 
-            let mut times = vec![];
-
-            let total = Instant::now();
-
-            while total.elapsed().as_millis() < 100 || times.len() < 3 {
-                let sub = &mut state.substate();
-                setup.call(sub)?;
-
-                let start = Instant::now();
-                runner.call(sub)?;
-                times.push(start.elapsed().as_nanos() as f64);
+    state.add_native_word(
+        "timeit-with",
+        "(..a setup( -- ..b) run(..b -- ..c) budget warmup -- ..a stats)",
+        Some(
+            "Like `timeit`, but run for budget milliseconds, after warmup runs that are not \
+             measured.",
+        ),
+        |state| {
+            let warmup = state.pop_i32()?;
+            let budget = state.pop_i32()?;
+            if budget < 0 || warmup < 0 {
+                return Err(ErrorKind::RuntimeError(
+                    "budget and warmup must not be negative".to_string(),
+                )
+                .into());
             }
+            let runner = state.pop()?;
+            let setup = state.pop()?;
+            let budget = Duration::from_millis(budget as u64);
+            let stats = measure(state, &setup, &runner, budget, warmup as usize)?;
+            state.push(stats.to_table()?)
+        },
+    );
 
-            let mut mean = times.iter().sum::<f64>() / times.len() as f64;
-            let var = times.iter().map(|t| (t - mean) * (t - mean)).sum::<f64>()
-                / (times.len() as f64 - 1.0);
-            let mut std = var.sqrt();
+    state.add_native_word(
+        ".stats",
+        "(stats -- )",
+        Some("Print the statistics pushed by `timeit`."),
+        |state| {
+            let stats = Stats::from_table(&state.pop()?)?;
+            writeln!(state.output(), "{}", stats)?;
+            Ok(())
+        },
+    );
 
-            let mut mean_units = -9;
-            let mut std_units = -9;
+    state.add_native_word(
+        "compare",
+        "(..a setup( -- ..b) runs -- ..a)",
+        Some(
+            "Time each quotation in the list runs like `timeit`, with the same setup, and print \
+             them from fastest to slowest, relative to the fastest.",
+        ),
+        |state| {
+            let runners = state.pop()?;
+            let setup = state.pop()?;
 
-            while mean >= 1e3 && mean_units < 0 {
-                mean /= 1e3;
-                mean_units += 3
+            let mut results = vec![];
+            for runner in runners.as_slice()? {
+                let stats = measure(state, &setup, runner, DEFAULT_BUDGET, 0)?;
+                results.push((decompile_object(state, runner), stats));
             }
+            results.sort_by(|(_, a), (_, b)| a.mean.partial_cmp(&b.mean).unwrap());
 
-            while std >= 1e3 && std_units < 0 {
-                std /= 1e3;
-                std_units += 3
+            let fastest = match results.first() {
+                Some((_, stats)) => stats.mean,
+                None => return Ok(()),
+            };
+            let width = results
+                .iter()
+                .map(|(label, _)| label.chars().count())
+                .max()
+                .unwrap_or(0);
+            for (i, (label, stats)) in results.iter().enumerate() {
+                writeln!(
+                    state.output(),
+                    "{}. {:width$}  {:>10} ± {:>10}  {:>6.2}x",
+                    i + 1,
+                    label,
+                    format_time(stats.mean),
+                    format_time(stats.std),
+                    stats.mean / fastest,
+                    width = width
+                )?;
             }
+            Ok(())
+        },
+    );
 
-            println!(
-                "{} runs, average {:.1} {} (± {:.1} {} std)",
-                times.len(),
-                mean,
-                time_units(mean_units),
-                std,
-                time_units(std_units)
-            );
-
+    state.add_native_word(
+        "profile",
+        "(..a quot(..a -- ..b) -- ..b)",
+        Some(
+            "Call a quotation and print how often each word was called and how much time was \
+             spent in it.",
+        ),
+        |state| {
+            let quot = state.pop()?;
+            let profiler = Profiler::new();
+            profiler.run(state, |state| quot.call(state))?;
+            write!(state.output(), "{}", profiler.report())?;
             Ok(())
         },
     );
 
+    state.add_native_word(
+        "profile-folded",
+        "(..a quot(..a -- ..b) -- ..b s)",
+        Some(
+            "Call a quotation and push the time spent per call stack in the folded format of \
+             flame graph tools.",
+        ),
+        |state| {
+            let quot = state.pop()?;
+            let profiler = Profiler::new();
+            profiler.run(state, |state| quot.call(state))?;
+            state.push_string(profiler.folded())
+        },
+    );
+
     state.exit_mod().unwrap();
 
     Ok(())
 }
 
-fn time_units(exp: i32) -> &'static str {
-    match exp {
-        0 => "s",
-        -3 => "ms",
-        -6 => "µs",
-        -9 => "ns",
-        _ => panic!("Invalid time exponent: {}", exp),
+/// Format a time given in nanoseconds with a suitable unit.
+fn format_time(ns: f64) -> String {
+    let (unit, scale) = Stats::UNITS
+        .iter()
+        .rev()
+        .find(|(_, scale)| ns >= *scale)
+        .unwrap_or(&Stats::UNITS[0]);
+    format!("{:.1} {}", ns / scale, unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{scope, stdlib, tier0};
+    use forthen_core::Output;
+
+    #[test]
+    fn format_times() {
+        assert_eq!(format_time(0.5), "0.5 ns");
+        assert_eq!(format_time(12.0), "12.0 ns");
+        assert_eq!(format_time(2500.0), "2.5 µs");
+        assert_eq!(format_time(3e7), "30.0 ms");
+        assert_eq!(format_time(1e13), "10000.0 s");
+    }
+
+    #[test]
+    fn stats_words() {
+        let state = &mut State::new();
+        stdlib(state).unwrap();
+        state.run("USE std: USE :std:timeit:").unwrap();
+
+        state.run("[ 1 ] [ 2 swap ] 0 2 timeit-with").unwrap();
+        let stats = Stats::from_table(&state.pop().unwrap()).unwrap();
+        assert_eq!(stats.runs, MIN_RUNS);
+        assert!(stats.min <= stats.median && stats.median <= stats.p95);
+        assert!(state.run("[ ] [ ] -1 0 timeit-with").is_err());
+
+        let (output, buffer) = Output::capture();
+        state.set_output(output);
+        state.run("[ ] [ ] 0 0 timeit-with .stats").unwrap();
+        assert!(buffer.contents().starts_with("3 runs, mean "));
+        assert!(state.run("{} .stats").is_err());
+    }
+
+    #[test]
+    fn compare_word() {
+        let state = &mut State::new();
+        stdlib(state).unwrap();
+        state.run("USE std: USE :std:timeit:").unwrap();
+
+        let (output, buffer) = Output::capture();
+        state.set_output(output);
+        state.run("[ 1 2 ] [ [ swap ] [ ] ] compare").unwrap();
+        state.assert_stack::<i32>(&[]);
+        let report = buffer.contents();
+        let lines: Vec<_> = report.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("1. "));
+        assert!(lines[0].ends_with(" 1.00x"));
+        assert!(lines[1].starts_with("2. "));
+    }
+
+    #[test]
+    fn stats_from_times() {
+        let stats = Stats::from_times(vec![4.0, 1.0, 3.0, 2.0]);
+        assert_eq!(stats.runs, 4);
+        assert_eq!(stats.mean, 2.5);
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.median, 2.5);
+        assert_eq!(stats.p95, 4.0);
+    }
+
+    #[test]
+    fn stats_tables() {
+        let fast = Stats::from_times(vec![40.0, 60.0, 50.0]);
+        let table = fast.to_table().unwrap();
+        assert_eq!(
+            &*table
+                .get_attr("unit")
+                .unwrap()
+                .try_into_rc_string()
+                .unwrap(),
+            "ns"
+        );
+        assert_eq!(Stats::from_table(&table).unwrap(), fast);
+
+        // three seconds do not fit into an i32 of nanoseconds
+        let slow = Stats::from_times(vec![3e9, 3e9, 3e9]);
+        let table = slow.to_table().unwrap();
+        assert_eq!(
+            &*table
+                .get_attr("unit")
+                .unwrap()
+                .try_into_rc_string()
+                .unwrap(),
+            "µs"
+        );
+        assert_eq!(
+            table.get_attr("mean").unwrap().try_into_i32().unwrap(),
+            3_000_000
+        );
+        assert_eq!(Stats::from_table(&table).unwrap().mean, 3e9);
+
+        assert!(Stats::from_times(vec![1e20, 1e20]).to_table().is_err());
+    }
+
+    #[test]
+    fn profile_words() {
+        let state = &mut State::new();
+        tier0(state).unwrap();
+        scope(state).unwrap();
+        timeit(state).unwrap();
+        state.run("USE timeit:").unwrap();
+        state
+            .run(": one ( -- x ) 1 ; : two ( -- x y ) one one ;")
+            .unwrap();
+
+        let (output, buffer) = Output::capture();
+        state.set_output(output);
+        state.run("[ two one ] profile").unwrap();
+        state.assert_stack(&[1, 1, 1]);
+        let report = buffer.contents();
+        let lines: Vec<_> = report.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].ends_with("calls     total ms      self ms  word"));
+        assert!(report.contains("         3 "));
+        assert!(report.contains("  ::one\n"));
+
+        state.run("[ two ] profile-folded").unwrap();
+        let folded = state.pop_string().unwrap();
+        let stacks: Vec<_> = folded
+            .lines()
+            .map(|line| line.rsplit_once(' ').unwrap().0)
+            .collect();
+        assert_eq!(stacks, vec!["::two", "::two;::one"]);
+
+        // profilers can be nested
+        state.clear_stack();
+        state.run("[ 1 ] [ profile ] profile").unwrap();
+        state.assert_stack(&[1]);
+        assert!(state.hooks().is_empty());
     }
 }
@@ -0,0 +1,31 @@
+use forthen_core::testing::run_golden_with;
+use forthen_core::State;
+use forthen_std::stdlib;
+
+fn run_example(name: &str) {
+    let state = &mut State::new();
+    stdlib(state).unwrap();
+    run_golden_with(state, format!("../examples/{}.fn", name));
+}
+
+#[test]
+fn hello() {
+    run_example("hello");
+}
+
+#[test]
+fn complex() {
+    run_example("complex");
+    run_example("tests/complex");
+}
+
+#[test]
+fn interpreter() {
+    run_example("interpreter");
+    run_example("tests/interpreter");
+}
+
+#[test]
+fn benchmark() {
+    run_example("benchmark");
+}